@@ -0,0 +1,161 @@
+// Draws a sprite together with its drop shadow and lets it wiggle.
+// This is the GPU version of `game::shadow` and `game::wiggle`.
+
+#import bevy_sprite::mesh2d_functions::{get_tag, get_world_from_local, mesh2d_position_local_to_clip}
+#import bevy_sprite::mesh2d_view_bindings::globals
+
+struct ShadowWiggle {
+    color: vec4<f32>,
+    shadow_color: vec4<f32>,
+    // xy: direction of a directional light or position of a point light
+    // z: height of a point light, w: one for a point light, zero otherwise
+    light: vec4<f32>,
+    // size of the sprite, the quad is twice as large to fit the shadow
+    size: vec2<f32>,
+    shadow_height: f32,
+    frequency: f32,
+    scale_rotation: f32,
+    scale_transform: f32,
+    // zero for a continuous wiggle
+    steps_per_second: f32,
+    // 0: value noise, 1: gradient noise, 2: cellular noise
+    noise: u32,
+    // added to the time of the game to get the time of the clock the wiggle follows
+    time_offset: f32,
+}
+
+@group(2) @binding(0) var<uniform> material: ShadowWiggle;
+@group(2) @binding(1) var sprite_texture: texture_2d<f32>;
+@group(2) @binding(2) var sprite_sampler: sampler;
+@group(2) @binding(3) var shadow_texture: texture_2d<f32>;
+@group(2) @binding(4) var shadow_sampler: sampler;
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+    @location(0) position: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) shadow_uv: vec2<f32>,
+};
+
+fn hash(x: f32) -> f32 {
+    return fract(sin(x * 127.1) * 43758.5453);
+}
+
+// smooth value noise in the range of -1 to 1
+fn value_noise(x: f32) -> f32 {
+    let i = floor(x);
+    let f = fract(x);
+    let u = f * f * (3.0 - 2.0 * f);
+    return mix(hash(i), hash(i + 1.0), u) * 2.0 - 1.0;
+}
+
+// perlin style gradient noise in the range of -1 to 1
+fn gradient_noise(x: f32) -> f32 {
+    let i = floor(x);
+    let f = fract(x);
+    let u = f * f * f * (f * (f * 6.0 - 15.0) + 10.0);
+    let g0 = hash(i) * 2.0 - 1.0;
+    let g1 = hash(i + 1.0) * 2.0 - 1.0;
+    return mix(g0 * f, g1 * (f - 1.0), u) * 2.0;
+}
+
+// distance to the closest random point in the range of -1 to 1
+fn cellular_noise(x: f32) -> f32 {
+    let i = floor(x);
+    var distance = 1.0;
+    for (var k = -1.0; k <= 1.0; k += 1.0) {
+        let point = i + k + hash(i + k);
+        distance = min(distance, abs(x - point));
+    }
+    return distance * 2.0 - 1.0;
+}
+
+fn noise(x: f32) -> f32 {
+    switch material.noise {
+        case 1u: {
+            return gradient_noise(x);
+        }
+        case 2u: {
+            return cellular_noise(x);
+        }
+        default: {
+            return value_noise(x);
+        }
+    }
+}
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    let world_from_local = get_world_from_local(vertex.instance_index);
+    let origin = world_from_local[3].xy;
+
+    // every sprite gets its own seed from the tag of its mesh, which stays
+    // the same while the sprite moves
+    let seed = hash(f32(get_tag(vertex.instance_index) & 0xffffu)) * 1000.0;
+    var time = globals.time + material.time_offset;
+    if material.steps_per_second > 0.0 {
+        // hold each pose for a full step
+        time = floor(time * material.steps_per_second) / material.steps_per_second;
+    }
+
+    let t = time * material.frequency + seed;
+
+    let angle = noise(t) * material.scale_rotation;
+    let offset = vec2(noise(t + 10.0), noise(t + 20.0)) * material.scale_transform;
+
+    let c = cos(angle);
+    let s = sin(angle);
+    let p = vertex.position.xy;
+    let position = vec2(c * p.x - s * p.y, s * p.x + c * p.y) + offset;
+
+    // offset and scale of the shadow in world space
+    var shadow_offset = material.light.xy * material.shadow_height;
+    var shadow_scale = 1.0;
+
+    if material.light.w > 0.5 {
+        let light_height = material.light.z;
+        let height = min(material.shadow_height, light_height * 0.9);
+        shadow_scale = light_height / (light_height - height);
+        shadow_offset = (origin - material.light.xy) * (shadow_scale - 1.0);
+    }
+
+    // bring the offset into local space
+    let m = mat2x2<f32>(world_from_local[0].xy, world_from_local[1].xy);
+    let det = m[0].x * m[1].y - m[1].x * m[0].y;
+    let local_from_world = mat2x2<f32>(
+        vec2(m[1].y, -m[0].y) / det,
+        vec2(-m[1].x, m[0].x) / det,
+    );
+
+    // uv space has y pointing downwards
+    let uv_offset = (local_from_world * shadow_offset) / (2.0 * material.size) * vec2(1.0, -1.0);
+
+    var out: VertexOutput;
+    out.clip_position = mesh2d_position_local_to_clip(world_from_local, vec4(position, 0.0, 1.0));
+    out.uv = (vertex.uv - 0.5) * 2.0 + 0.5;
+    out.shadow_uv = (vertex.uv - 0.5 - uv_offset) / shadow_scale + 0.5;
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    // the sprite only covers the center of the quad
+    let inside = step(0.0, in.uv.x) * step(in.uv.x, 1.0) * step(0.0, in.uv.y) * step(in.uv.y, 1.0);
+
+    let sprite = textureSample(sprite_texture, sprite_sampler, in.uv) * material.color;
+    let shadow = textureSample(shadow_texture, shadow_sampler, in.shadow_uv);
+
+    let sprite_alpha = sprite.a * inside;
+    let shadow_alpha = material.shadow_color.a * shadow.a * (1.0 - sprite_alpha);
+
+    // draw the sprite on top of its shadow
+    let alpha = sprite_alpha + shadow_alpha;
+    let color = (sprite.rgb * sprite_alpha + material.shadow_color.rgb * shadow_alpha) / max(alpha, 0.0001);
+
+    return vec4(color, alpha);
+}
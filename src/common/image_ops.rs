@@ -0,0 +1,109 @@
+//! Image processing used to preprocess the game's images, see `crate::preprocess`.
+
+use image::{GrayAlphaImage, LumaA, Rgba, RgbaImage, imageops};
+
+/// Finds the smallest rectangle containing all non-transparent pixels
+/// as `[min_x, min_y, max_x, max_y]`, with exclusive max coordinates.
+/// Returns an empty rectangle if all pixels are transparent.
+pub fn find_crop_rect(image: &RgbaImage) -> [u32; 4] {
+    let is_visible = |pixel: &Rgba<u8>| pixel[3] != 0;
+
+    // get the first row that has a non-zero alpha component
+    let Some(min_y) = image
+        .rows()
+        .enumerate()
+        .find_map(|(y, mut pixels)| pixels.any(is_visible).then_some(y as u32))
+    else {
+        return [0; 4];
+    };
+
+    // get the last row that has a non-zero alpha component
+    let max_y = image
+        .rows()
+        .enumerate()
+        .rev()
+        .find_map(|(y, mut pixels)| pixels.any(is_visible).then_some(y as u32))
+        .unwrap_or(min_y);
+
+    // get the first column that has a non zero alpha component
+    let min_x = (0..image.width())
+        .find(|&x| (0..image.height()).any(|y| is_visible(image.get_pixel(x, y))))
+        .unwrap_or_default();
+
+    // get the last column that has a non zero alpha component
+    let max_x = (0..image.width())
+        .rev()
+        .find(|&x| (0..image.height()).any(|y| is_visible(image.get_pixel(x, y))))
+        .unwrap_or(min_x);
+
+    [min_x, min_y, max_x + 1, max_y + 1]
+}
+
+/// Creates a soft shadow from the alpha channel of the image. The shadow is
+/// twice the size of the image, with the image's silhouette in its center.
+/// The shadow is white and only the alpha channel carries information.
+pub fn generate_shadow_from_alpha(image: &RgbaImage, sigma: f32) -> GrayAlphaImage {
+    // start with a transparent black image
+    let mut mask = GrayAlphaImage::new(image.width() * 2, image.height() * 2);
+
+    let offset_x = image.width() / 2;
+    let offset_y = image.height() / 2;
+
+    // copy alpha channel into the center of the mask image
+    for (src, dst) in image.rows().zip(mask.rows_mut().skip(offset_y as usize)) {
+        for (Rgba(sp), LumaA(dp)) in src.zip(dst.skip(offset_x as usize)) {
+            dp[1] = sp[3];
+        }
+    }
+
+    // increase the size of the mask by first applying a small blur
+    let mut mask = imageops::fast_blur(&mask, 1.0);
+
+    // and then using a threshold
+    mask.pixels_mut()
+        .for_each(|LumaA([_, px])| *px = px.saturating_mul(5));
+
+    // now blur the mask to create the base shadow
+    let mut shadow = GrayAlphaImage::new(mask.width(), mask.height());
+
+    add(&mut shadow, &imageops::fast_blur(&mask, sigma), 2);
+
+    // reduce influence of the mask for pixels with wider shadow blur
+    add(&mut shadow, &imageops::fast_blur(&mask, sigma * 2.0), 3);
+
+    // reduce influence of the mask for pixels with wider shadow blur
+    add(&mut shadow, &imageops::fast_blur(&mask, sigma * 4.0), 4);
+
+    // make the shadow white, so it can be tinted using the sprite color
+    shadow
+        .pixels_mut()
+        .for_each(|LumaA([luma, _])| *luma = u8::MAX);
+
+    shadow
+}
+
+#[inline]
+fn add(target: &mut GrayAlphaImage, source: &GrayAlphaImage, divider: u8) {
+    for (LumaA([_, tp]), LumaA([_, sp])) in target.pixels_mut().zip(source.pixels()) {
+        *tp = tp.saturating_add(*sp / divider);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crop_rect_includes_last_visible_row_and_column() {
+        let mut image = RgbaImage::new(6, 5);
+        image.put_pixel(1, 1, Rgba([255, 255, 255, 255]));
+        image.put_pixel(3, 2, Rgba([255, 255, 255, 1]));
+
+        assert_eq!(find_crop_rect(&image), [1, 1, 4, 3]);
+    }
+
+    #[test]
+    fn crop_rect_of_transparent_image_is_empty() {
+        assert_eq!(find_crop_rect(&RgbaImage::new(4, 4)), [0; 4]);
+    }
+}
@@ -2,7 +2,7 @@ use crate::AppSystems;
 use bevy::prelude::*;
 
 pub fn plugin(app: &mut App) {
-    app.add_systems(Update, update_markers.in_set(AppSystems::Update));
+    app.add_systems(Update, update_markers.in_set(AppSystems::UpdateMarkers));
 }
 
 pub enum MarkerTarget {
@@ -10,6 +10,16 @@ pub enum MarkerTarget {
     Dynamic { target: Entity, offset: Vec2 },
 }
 
+impl MarkerTarget {
+    /// Resolves the world position of this target, if the target still exists.
+    pub fn position(&self, lookup: impl Fn(Entity) -> Option<Vec2>) -> Option<Vec2> {
+        match self {
+            MarkerTarget::Static { position } => Some(*position),
+            MarkerTarget::Dynamic { target, offset } => Some(lookup(*target)? + *offset),
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct Marker {
     pub follow: Entity,
@@ -18,25 +28,20 @@ pub struct Marker {
     pub offset_z: f32,
 }
 
-fn update_markers(
+pub(crate) fn update_markers(
     mut commands: Commands,
     mut markers: Query<(Entity, &mut Transform, &Marker)>,
     transforms: Query<&Transform, Without<Marker>>,
 ) {
     for (marker_entity, mut marker_transform, marker) in markers.iter_mut() {
-        let target = match &marker.target {
-            MarkerTarget::Static { position } => *position,
-            MarkerTarget::Dynamic { target, offset } => {
-                let Ok(transform) = transforms.get(*target) else {
-                    continue;
-                };
-
-                transform.translation.xy() + *offset
-            }
-        };
+        let target = marker.target.position(|target| {
+            let transform = transforms.get(target).ok()?;
+            Some(transform.translation.xy())
+        });
 
-        let direction = (target - marker_transform.translation.xy()).normalize();
-        let offset = (direction * marker.offset).extend(marker.offset_z);
+        let Some(target) = target else {
+            continue;
+        };
 
         let Ok(base) = transforms.get(marker.follow) else {
             // follow is gone, despawn marker
@@ -44,6 +49,9 @@ fn update_markers(
             continue;
         };
 
+        let direction = (target - base.translation.xy()).normalize_or(Vec2::X);
+        let offset = (direction * marker.offset).extend(marker.offset_z);
+
         marker_transform.translation = base.translation + offset;
         marker_transform.rotation = Quat::from_rotation_z(direction.to_angle());
     }
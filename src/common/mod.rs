@@ -1,6 +1,7 @@
 use bevy::app::App;
 
 pub mod cursor;
+pub mod image_ops;
 pub mod markers;
 pub mod pause;
 pub mod rand;
@@ -16,3 +17,8 @@ pub fn plugin(app: &mut App) {
         pause::plugin,
     ));
 }
+
+/// The common plugins needed to simulate the game without a window.
+pub fn headless_plugin(app: &mut App) {
+    app.add_plugins((rand::plugin, pause::plugin));
+}
@@ -25,3 +25,28 @@ fn pause_stop_time(mut time: ResMut<Time<Virtual>>) {
 fn pause_resume_time(mut time: ResMut<Time<Virtual>>) {
     time.unpause();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestApp;
+
+    #[test]
+    fn paused_game_stands_still() {
+        let mut app = TestApp::new();
+        app.spawn_attractor(vec2(0.0, -400.0), 128.0);
+        let rocket = app.spawn_rocket(Vec2::ZERO);
+
+        app.advance_secs(0.5);
+
+        app.set_state(Pause(true));
+        let position = app.position(rocket);
+
+        app.advance_secs(1.0);
+        assert_eq!(app.position(rocket), position);
+
+        app.set_state(Pause(false));
+        app.advance_secs(1.0);
+        assert!(app.position(rocket).y < position.y);
+    }
+}
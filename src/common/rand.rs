@@ -27,6 +27,11 @@ impl RngCore for Rand {
 }
 
 impl Rand {
+    /// Creates a new generator from the given seed, independent of the global one.
+    pub fn seeded(seed: u64) -> Self {
+        Self(rand::rngs::SmallRng::seed_from_u64(seed))
+    }
+
     /// Returns a random vec2 within the unit circle.
     pub fn vec2(&mut self) -> Vec2 {
         loop {
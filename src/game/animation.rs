@@ -0,0 +1,198 @@
+//! Sprite-sheet animations.
+//!
+//! A [`SpriteAnimation`] steps through frames from a texture atlas, a list of
+//! images or a list of cropped images. Only the sprite is changed, shadows
+//! pick up the new frame by themselves. Sprites drawn by the shader of
+//! `sprite_effects` are animated the same way.
+
+use crate::AppSystems;
+use crate::common::pause::PausableSystems;
+use crate::game::planet::Cropped;
+use crate::game::sprite_effects::EffectSprite;
+use crate::game::wiggle::Wiggle;
+use bevy::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        update_animations
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+}
+
+#[derive(Clone, Reflect)]
+pub enum AnimationFrames {
+    /// Indices into the texture atlas of the sprite, including `last`.
+    Atlas { first: usize, last: usize },
+
+    /// Full images, one per frame.
+    Images(Vec<Handle<Image>>),
+
+    /// Images that were cropped to their visible pixels, one per frame.
+    /// Each frame is placed within the given size, like the uncropped image would be.
+    Cropped { frames: Vec<Cropped>, size: Vec2 },
+}
+
+impl AnimationFrames {
+    pub fn len(&self) -> usize {
+        match self {
+            AnimationFrames::Atlas { first, last } => last.saturating_sub(*first) + 1,
+            AnimationFrames::Images(images) => images.len(),
+            AnimationFrames::Cropped { frames, .. } => frames.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+pub enum AnimationMode {
+    /// Plays the animation once and stops at the last frame.
+    Once,
+    /// Starts over after the last frame.
+    #[default]
+    Loop,
+    /// Plays the animation forwards and backwards again.
+    PingPong,
+}
+
+#[derive(Component, Clone, Reflect)]
+pub struct SpriteAnimation {
+    pub frames: AnimationFrames,
+    pub fps: f32,
+    pub mode: AnimationMode,
+    elapsed: f32,
+    current: Option<usize>,
+}
+
+impl SpriteAnimation {
+    pub fn new(frames: AnimationFrames, fps: f32, mode: AnimationMode) -> Self {
+        Self {
+            frames,
+            fps,
+            mode,
+            elapsed: 0.0,
+            current: None,
+        }
+    }
+
+    /// Starts the animation at a later point, e.g. to not have all animations in sync.
+    pub fn with_offset(mut self, seconds: f32) -> Self {
+        self.elapsed = seconds;
+        self
+    }
+
+    /// The index of the frame to show at the current time.
+    fn frame(&self) -> usize {
+        let len = self.frames.len();
+        if len <= 1 {
+            return 0;
+        }
+
+        let step = (self.elapsed * self.fps).floor() as usize;
+
+        match self.mode {
+            AnimationMode::Once => step.min(len - 1),
+            AnimationMode::Loop => step % len,
+            AnimationMode::PingPong => {
+                let period = 2 * (len - 1);
+                let step = step % period;
+                if step < len { step } else { period - step }
+            }
+        }
+    }
+}
+
+fn update_animations(
+    time: Res<Time>,
+    animations: Query<(
+        &mut SpriteAnimation,
+        AnyOf<(&mut Sprite, &mut EffectSprite)>,
+        &mut Transform,
+        Option<&mut Wiggle>,
+    )>,
+) {
+    for (mut animation, sprites, mut transform, wiggle) in animations {
+        animation.elapsed += time.delta_secs();
+
+        let frame = animation.frame();
+        if animation.current == Some(frame) || animation.frames.is_empty() {
+            continue;
+        }
+
+        animation.current = Some(frame);
+
+        let sprite = match sprites {
+            (Some(sprite), _) => sprite.into_inner(),
+            (_, Some(effect)) => &mut effect.into_inner().0,
+            (None, None) => continue,
+        };
+
+        match &animation.frames {
+            AnimationFrames::Atlas { first, .. } => {
+                if let Some(atlas) = &mut sprite.texture_atlas {
+                    atlas.index = first + frame;
+                }
+            }
+
+            AnimationFrames::Images(images) => {
+                sprite.image = images[frame].clone();
+            }
+
+            AnimationFrames::Cropped { frames, size } => {
+                let cropped = &frames[frame];
+                let (offset, frame_size) = cropped.placement(*size);
+
+                sprite.image = cropped.handle.clone();
+                sprite.custom_size = Some(frame_size);
+
+                // the wiggle owns the translation if there is one
+                match wiggle {
+                    Some(mut wiggle) => wiggle.offset = offset,
+                    None => {
+                        transform.translation.x = offset.x;
+                        transform.translation.y = offset.y;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The frames shown during the first seconds of a four frame animation at one frame per second.
+    fn frames(mode: AnimationMode, seconds: usize) -> Vec<usize> {
+        let frames = AnimationFrames::Atlas { first: 0, last: 3 };
+
+        (0..seconds)
+            .map(|second| {
+                let animation = SpriteAnimation::new(frames.clone(), 1.0, mode);
+                animation.with_offset(second as f32 + 0.5).frame()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn once_stops_at_last_frame() {
+        assert_eq!(frames(AnimationMode::Once, 6), [0, 1, 2, 3, 3, 3]);
+    }
+
+    #[test]
+    fn loop_starts_over() {
+        assert_eq!(frames(AnimationMode::Loop, 6), [0, 1, 2, 3, 0, 1]);
+    }
+
+    #[test]
+    fn ping_pong_plays_backwards() {
+        assert_eq!(
+            frames(AnimationMode::PingPong, 9),
+            [0, 1, 2, 3, 2, 1, 0, 1, 2]
+        );
+    }
+}
@@ -1,80 +1,191 @@
 use crate::asset_tracking::LoadResource;
+use crate::game::planet::{Cropped, LayerMotion, PlanetLayer};
+use crate::game::processed;
 use bevy::prelude::*;
 
 pub fn plugin(app: &mut App) {
     app.load_resource::<Assets>();
 }
 
+/// Number of frames in the flame sprite sheet, placed side by side.
+pub const FLAME_FRAMES: u32 = 4;
+
+/// Size of a single frame of the flame.
+pub const FLAME_SIZE: UVec2 = UVec2::new(32, 64);
+
 #[derive(Clone, Resource, Asset, TypePath)]
 pub struct Assets {
     pub rocket_base: Handle<Image>,
     pub rocket_fin_bg: Handle<Image>,
     pub rocket_fin_fg: Handle<Image>,
+    pub rocket_booster: Handle<Image>,
+    /// A sprite sheet of the engine flame, see [`FLAME_FRAMES`].
+    pub flame: Handle<Image>,
+    pub flame_layout: Handle<TextureAtlasLayout>,
 
     pub star_small: Handle<Image>,
     pub star_large: Handle<Image>,
     pub line: Handle<Image>,
-    pub plume: [Handle<Image>; 3],
 
     pub planets: Vec<PlanetAssets>,
 }
 
+impl Assets {
+    /// Assets without any images, for simulating a level without rendering.
+    /// Provides the given number of planets, which have no layers.
+    pub fn placeholder(planets: usize) -> Self {
+        Self {
+            rocket_base: Handle::default(),
+            rocket_fin_bg: Handle::default(),
+            rocket_fin_fg: Handle::default(),
+            rocket_booster: Handle::default(),
+            flame: Handle::default(),
+            flame_layout: Handle::default(),
+            star_small: Handle::default(),
+            star_large: Handle::default(),
+            line: Handle::default(),
+            planets: vec![PlanetAssets { layers: Vec::new() }; planets],
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct PlanetAssets {
-    pub images: Vec<Handle<Image>>,
+    pub layers: Vec<PlanetLayer>,
 }
 
 impl PlanetAssets {
-    pub fn new(images: impl Into<Vec<Handle<Image>>>) -> Self {
-        Self {
-            images: images.into(),
+    /// Loads the preprocessed layers of a planet, see `crate::preprocess`.
+    pub fn load(server: &AssetServer, sources: &[&str]) -> Self {
+        let layers: Vec<_> = sources.iter().map(std::slice::from_ref).collect();
+        Self::load_animated(server, &layers)
+    }
+
+    /// Loads the preprocessed layers of a planet, where each layer
+    /// is given as a list of animation frames.
+    pub fn load_animated(server: &AssetServer, layers: &[&[&str]]) -> Self {
+        let layers = layers
+            .iter()
+            .map(|frames| PlanetLayer {
+                frames: frames
+                    .iter()
+                    .map(|source| load_cropped(server, source))
+                    .collect(),
+                motion: LayerMotion::STILL,
+            })
+            .collect();
+
+        Self { layers }
+    }
+
+    /// Sets the motion of each layer, starting with the bottom layer.
+    pub fn with_motion(mut self, motions: &[LayerMotion]) -> Self {
+        for (layer, motion) in self.layers.iter_mut().zip(motions) {
+            layer.motion = *motion;
         }
+
+        self
+    }
+}
+
+fn load_cropped(server: &AssetServer, source: &str) -> Cropped {
+    let Some(crop) = processed::crop(source) else {
+        error!("No preprocessed image for {source}, run `gravitate preprocess`");
+        return Cropped::uncropped(server.load(source));
+    };
+
+    let [min_x, min_y, max_x, max_y] = crop.rect;
+
+    Cropped {
+        handle: server.load(crop.image.as_str()),
+        rect: URect::new(min_x, min_y, max_x, max_y),
+        full_size: UVec2::from(crop.full_size),
     }
 }
 
 impl FromWorld for Assets {
     fn from_world(world: &mut World) -> Self {
+        let flame_layout = world
+            .resource_mut::<bevy::prelude::Assets<TextureAtlasLayout>>()
+            .add(TextureAtlasLayout::from_grid(
+                FLAME_SIZE,
+                FLAME_FRAMES,
+                1,
+                None,
+                None,
+            ));
+
         let server = world.resource_mut::<AssetServer>();
 
         Self {
             rocket_base: server.load("images/rocket-base.png"),
             rocket_fin_bg: server.load("images/rocket-fin-bg.png"),
             rocket_fin_fg: server.load("images/rocket-fin-fg.png"),
+            rocket_booster: server.load("images/rocket-booster.png"),
+            flame: server.load("images/flame.png"),
+            flame_layout,
 
             star_small: server.load("images/star-small.png"),
             star_large: server.load("images/star-large.png"),
 
             line: server.load("images/line.png"),
 
-            plume: [
-                server.load("images/plume1.png"),
-                server.load("images/plume2.png"),
-                server.load("images/plume3.png"),
-            ],
-
             planets: vec![
-                PlanetAssets::new([
-                    server.load("images/planet-earth-1.png"),
-                    server.load("images/planet-earth-2.png"),
-                    server.load("images/planet-earth-3.png"),
-                    server.load("images/planet-earth-4.png"),
+                PlanetAssets::load(
+                    &server,
+                    &[
+                        "images/planet-earth-1.png",
+                        "images/planet-earth-2.png",
+                        "images/planet-earth-3.png",
+                        "images/planet-earth-4.png",
+                    ],
+                )
+                .with_motion(&[
+                    LayerMotion::STILL,
+                    LayerMotion::new(0.02, 0.01),
+                    LayerMotion::new(0.02, 0.02),
+                    // clouds drift over the surface
+                    LayerMotion::new(-0.06, 0.04),
                 ]),
-                PlanetAssets::new([
-                    server.load("images/planet-2-1.png"),
-                    server.load("images/planet-2-2.png"),
-                    server.load("images/planet-2-3.png"),
-                    server.load("images/planet-2-4.png"),
-                    server.load("images/planet-2-5.png"),
-                    server.load("images/planet-2-6.png"),
+                PlanetAssets::load(
+                    &server,
+                    &[
+                        "images/planet-2-1.png",
+                        "images/planet-2-2.png",
+                        "images/planet-2-3.png",
+                        "images/planet-2-4.png",
+                        "images/planet-2-5.png",
+                        "images/planet-2-6.png",
+                    ],
+                )
+                .with_motion(&[
+                    LayerMotion::STILL,
+                    LayerMotion::new(0.0, 0.01),
+                    LayerMotion::new(0.0, 0.015),
+                    LayerMotion::new(0.03, 0.02),
+                    LayerMotion::new(0.03, 0.03),
+                    LayerMotion::new(-0.05, 0.04),
                 ]),
-                PlanetAssets::new([
-                    server.load("images/planet-moon-1.png"),
-                    server.load("images/planet-moon-2.png"),
-                    server.load("images/planet-moon-3.png"),
-                    server.load("images/planet-moon-4.png"),
-                    server.load("images/planet-moon-5.png"),
-                    server.load("images/planet-moon-6.png"),
-                    server.load("images/planet-moon-7.png"),
+                PlanetAssets::load(
+                    &server,
+                    &[
+                        "images/planet-moon-1.png",
+                        "images/planet-moon-2.png",
+                        "images/planet-moon-3.png",
+                        "images/planet-moon-4.png",
+                        "images/planet-moon-5.png",
+                        "images/planet-moon-6.png",
+                        "images/planet-moon-7.png",
+                    ],
+                )
+                .with_motion(&[
+                    LayerMotion::STILL,
+                    LayerMotion::new(0.0, 0.005),
+                    LayerMotion::new(0.0, 0.01),
+                    LayerMotion::new(0.0, 0.015),
+                    LayerMotion::new(0.0, 0.02),
+                    LayerMotion::new(0.0, 0.025),
+                    LayerMotion::new(0.0, 0.03),
                 ]),
             ],
         }
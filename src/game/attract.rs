@@ -0,0 +1,329 @@
+//! A demo flight that plays behind the title screen while nobody touches the game.
+//!
+//! The autopilot plans a route through the sandbox level once, in the background
+//! while the title screen is shown. The rocket then follows the planned path, it
+//! is moved along the recorded points instead of being simulated. Any input stops
+//! the demo.
+
+use crate::game::autopilot::{Problem, STEP};
+use crate::game::config::GameConfig;
+use crate::game::level_file::LevelFile;
+use crate::game::rocket::RocketDef;
+use crate::game::{goal, planet, rocket};
+use crate::screens::Screen;
+use crate::screens::title::TitleBackground;
+use crate::{AppSystems, MainCamera, game};
+use avian2d::prelude::RigidBody;
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input::mouse::{MouseButtonInput, MouseMotion, MouseWheel};
+use bevy::input::touch::TouchInput;
+use bevy::math::ops::ln;
+use bevy::prelude::*;
+use bevy::tasks::futures::check_ready;
+use bevy::tasks::{AsyncComputeTaskPool, Task};
+use std::f32::consts::FRAC_PI_2;
+use std::time::Duration;
+
+/// Time without input before the demo starts.
+const IDLE_DELAY: Duration = Duration::from_secs(10);
+
+/// Time the rocket stays in the goal before the demo starts over.
+const LOOP_DELAY_SECS: f32 = 2.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<IdleTime>();
+
+    app.add_systems(OnEnter(Screen::Title), (reset_idle_time, plan_demo));
+    app.add_systems(OnExit(Screen::Title), stop_demo);
+
+    app.add_systems(
+        Update,
+        (
+            stop_demo_on_input,
+            tick_idle_time,
+            finish_demo_planning.run_if(resource_exists::<DemoPlanning>),
+            start_demo.run_if(not(resource_exists::<Demo>).and(resource_exists::<DemoPlan>)),
+            play_demo.run_if(resource_exists::<Demo>),
+        )
+            .chain()
+            .run_if(in_state(Screen::Title).and(resource_exists::<game::Assets>))
+            .in_set(AppSystems::Update),
+    );
+
+    app.add_systems(
+        Update,
+        follow_demo_rocket
+            .run_if(in_state(Screen::Title).and(resource_exists::<Demo>))
+            .in_set(AppSystems::UpdateCamera),
+    );
+}
+
+/// Time since the last input on the title screen.
+#[derive(Resource, Default)]
+struct IdleTime(Duration);
+
+/// The autopilot planning the demo flight.
+#[derive(Resource)]
+struct DemoPlanning(Task<Option<DemoFlight>>);
+
+/// The planned demo flight, `None` if the autopilot found no route.
+#[derive(Resource)]
+struct DemoPlan(Option<DemoFlight>);
+
+/// The demo that is currently playing.
+#[derive(Resource)]
+struct Demo {
+    flight: DemoFlight,
+    rocket: Entity,
+    elapsed: f32,
+}
+
+/// Positions and velocities of the rocket along the planned route, one point per step.
+#[derive(Clone)]
+struct DemoFlight {
+    points: Vec<(Vec2, Vec2)>,
+}
+
+impl DemoFlight {
+    fn plan(level: &LevelFile, config: &GameConfig) -> Option<Self> {
+        let problem = Problem::from_level(level, config)?;
+        let plan = problem.solve()?;
+
+        let mut points = vec![(problem.position, problem.velocity)];
+        problem.fly(&plan.burns, |_, position, velocity| {
+            points.push((position, velocity));
+        });
+
+        Some(Self { points })
+    }
+
+    fn duration(&self) -> f32 {
+        self.points.len() as f32 * STEP
+    }
+
+    /// Position and velocity of the rocket at the given time.
+    fn at(&self, time: f32) -> (Vec2, Vec2) {
+        let idx = ((time / STEP) as usize).min(self.points.len() - 1);
+        self.points[idx]
+    }
+}
+
+/// Everything spawned for the demo, despawned when the demo stops.
+#[derive(Component)]
+struct DemoEntity;
+
+#[derive(Component)]
+struct DemoRocket;
+
+fn reset_idle_time(mut idle: ResMut<IdleTime>) {
+    idle.0 = Duration::ZERO;
+}
+
+fn tick_idle_time(mut idle: ResMut<IdleTime>, time: Res<Time<Real>>) {
+    idle.0 += time.delta();
+}
+
+fn stop_demo_on_input(
+    mut commands: Commands,
+    mut idle: ResMut<IdleTime>,
+    mut keys: EventReader<KeyboardInput>,
+    mut buttons: EventReader<MouseButtonInput>,
+    mut motion: EventReader<MouseMotion>,
+    mut wheel: EventReader<MouseWheel>,
+    mut touches: EventReader<TouchInput>,
+) {
+    // read all readers, so every event is consumed
+    let input = [
+        keys.read().count(),
+        buttons.read().count(),
+        motion.read().count(),
+        wheel.read().count(),
+        touches.read().count(),
+    ];
+
+    if input.iter().any(|&count| count > 0) {
+        idle.0 = Duration::ZERO;
+        commands.run_system_cached(stop_demo);
+    }
+}
+
+/// Planning takes a moment, it is only done once.
+fn plan_demo(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    planning: Option<Res<DemoPlanning>>,
+    plan: Option<Res<DemoPlan>>,
+) {
+    if planning.is_some() || plan.is_some() {
+        return;
+    }
+
+    let level = LevelFile::sandbox();
+    let config = config.clone();
+
+    let task = AsyncComputeTaskPool::get().spawn(async move { DemoFlight::plan(&level, &config) });
+    commands.insert_resource(DemoPlanning(task));
+}
+
+fn finish_demo_planning(mut commands: Commands, mut planning: ResMut<DemoPlanning>) {
+    let Some(flight) = check_ready(&mut planning.0) else {
+        return;
+    };
+
+    commands.remove_resource::<DemoPlanning>();
+    commands.insert_resource(DemoPlan(flight));
+}
+
+fn start_demo(
+    mut commands: Commands,
+    idle: Res<IdleTime>,
+    assets: Res<game::Assets>,
+    config: Res<GameConfig>,
+    plan: Res<DemoPlan>,
+    mut background: Query<&mut Visibility, With<TitleBackground>>,
+) {
+    if idle.0 < IDLE_DELAY {
+        return;
+    }
+
+    let Some(flight) = plan.0.clone() else {
+        return;
+    };
+
+    let level = LevelFile::sandbox();
+
+    info!("Start demo flight");
+
+    for def in &level.planets {
+        let Some(planet_assets) = assets.planets.get(def.assets) else {
+            continue;
+        };
+
+        commands.spawn((
+            StateScoped(Screen::Title),
+            DemoEntity,
+            Transform::from_translation(Vec2::from(def.position).extend(0.0)),
+            planet::bundle(planet_assets, def.radius),
+        ));
+    }
+
+    if let Some(goal) = &level.goal {
+        commands
+            .spawn((
+                Transform::from_translation(Vec2::from(goal.position).extend(0.0)),
+                goal::goal_bundle(&assets, goal.radius),
+            ))
+            .insert((StateScoped(Screen::Title), DemoEntity));
+    }
+
+    let rocket = RocketDef::new(level.rules.rocket, &assets, &config);
+
+    let rocket = commands
+        .spawn((
+            Name::new("DemoRocket"),
+            Transform::from_translation(Vec2::from(level.start).extend(0.0)),
+            rocket::bundle(&rocket),
+        ))
+        .insert((
+            StateScoped(Screen::Title),
+            DemoEntity,
+            DemoRocket,
+            // moved along the recorded path instead of by the physics
+            RigidBody::Kinematic,
+        ))
+        .id();
+
+    commands.insert_resource(Demo {
+        flight,
+        rocket,
+        elapsed: 0.0,
+    });
+
+    for mut visibility in &mut background {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+fn play_demo(
+    mut demo: ResMut<Demo>,
+    time: Res<Time<Real>>,
+    mut rockets: Query<&mut Transform, With<DemoRocket>>,
+) {
+    demo.elapsed += time.delta_secs();
+
+    if demo.elapsed > demo.flight.duration() + LOOP_DELAY_SECS {
+        demo.elapsed = 0.0;
+    }
+
+    let (position, velocity) = demo.flight.at(demo.elapsed);
+
+    let Ok(mut transform) = rockets.get_mut(demo.rocket) else {
+        return;
+    };
+
+    transform.translation = position.extend(transform.translation.z);
+
+    if velocity.length_squared() > 1.0 {
+        // the nose of the rocket points up
+        transform.rotation = Quat::from_rotation_z(velocity.to_angle() - FRAC_PI_2);
+    }
+}
+
+fn follow_demo_rocket(
+    mut camera: Single<&mut Transform, With<MainCamera>>,
+    rockets: Query<&Transform, (With<DemoRocket>, Without<MainCamera>)>,
+    time: Res<Time<Real>>,
+) {
+    let Ok(rocket) = rockets.single() else {
+        return;
+    };
+
+    let mut current = camera.translation.xy();
+    current.smooth_nudge(&rocket.translation.xy(), ln(5.0), time.delta_secs());
+
+    camera.translation.x = current.x;
+    camera.translation.y = current.y;
+}
+
+fn stop_demo(
+    mut commands: Commands,
+    demo_entities: Query<Entity, With<DemoEntity>>,
+    mut background: Query<&mut Visibility, With<TitleBackground>>,
+    mut camera: Single<&mut Transform, With<MainCamera>>,
+    demo: Option<Res<Demo>>,
+) {
+    if demo.is_none() {
+        return;
+    }
+
+    info!("Stop demo flight");
+
+    commands.remove_resource::<Demo>();
+
+    for entity in &demo_entities {
+        commands.entity(entity).despawn();
+    }
+
+    for mut visibility in &mut background {
+        *visibility = Visibility::Inherited;
+    }
+
+    camera.translation.x = 0.0;
+    camera.translation.y = 0.0;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sandbox_has_demo_flight() {
+        let level = LevelFile::sandbox();
+        let flight = DemoFlight::plan(&level, &GameConfig::default()).expect("route exists");
+
+        let goal = level.goal.unwrap();
+        let (end, _) = flight.at(flight.duration());
+
+        assert!(end.distance(Vec2::from(goal.position)) <= goal.radius);
+    }
+}
@@ -20,6 +20,13 @@ pub struct Attractor;
 #[derive(Component, Reflect)]
 pub struct Attractable;
 
+/// The force an attractor of the given mass applies to something at `position`.
+pub fn attraction_force(attractor: Vec2, attractor_mass: f32, position: Vec2) -> Vec2 {
+    let direction = attractor - position;
+    let amount = attractor_mass / direction.length_squared();
+    direction.normalize() * amount
+}
+
 fn apply_attraction_forces(
     attractors: Query<(&Transform, &ComputedMass), With<Attractor>>,
     attractable: Query<(&Transform, &mut ExternalForce), With<Attractable>>,
@@ -28,9 +35,41 @@ fn apply_attraction_forces(
 
     for (transform, mut force) in attractable {
         for (attr_transform, attr_mass) in &attractors {
-            let direction = attr_transform.translation.xy() - transform.translation.xy();
-            let amount = attr_mass.value() / direction.length_squared();
-            force.apply_force(direction.normalize() * amount);
+            force.apply_force(attraction_force(
+                attr_transform.translation.xy(),
+                attr_mass.value(),
+                transform.translation.xy(),
+            ));
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{TestApp, assert_near};
+
+    #[test]
+    fn force_points_towards_attractor() {
+        let force = attraction_force(vec2(100.0, 0.0), 1000.0, Vec2::ZERO);
+
+        assert!(force.x > 0.0);
+        assert_eq!(force.y, 0.0);
+
+        // falls off with the square of the distance
+        assert_near(force.length(), 1000.0 / (100.0 * 100.0), 1e-6);
+    }
+
+    #[test]
+    fn rocket_falls_towards_planet() {
+        let mut app = TestApp::new();
+        app.spawn_attractor(vec2(0.0, -400.0), 128.0);
+        let rocket = app.spawn_rocket(Vec2::ZERO);
+
+        app.advance_secs(1.0);
+
+        assert!(app.position(rocket).y < 0.0);
+        assert!(app.velocity(rocket).y < 0.0);
+        assert_near(app.position(rocket).x, 0.0, 1e-3);
+    }
+}
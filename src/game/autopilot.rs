@@ -0,0 +1,518 @@
+//! Searches for burns that fly the rocket into the goal with as little fuel
+//! as possible.
+//!
+//! Trajectories are simulated with the same attraction model the predicted
+//! path uses (see `trajectory`). The search is a beam search: it tries every
+//! combination of direction and duration for the first burn, keeps the plans
+//! that got closest to the goal and extends them with another burn after
+//! coasting for a while. Only the burning stage is used, pickups are ignored.
+//!
+//! The rules of the level are followed: with realistic attitude control, a burn
+//! only starts once the rocket has turned into its direction, and the rocket gets
+//! lighter while burning if fuel has mass. A new burn replaces the previous one,
+//! just like a new thrust does in the game.
+
+use crate::game;
+use crate::game::attraction::attraction_force;
+use crate::game::config::GameConfig;
+use crate::game::level::LevelRules;
+use crate::game::level_file::LevelFile;
+use crate::game::rocket::{AttitudeControl, RocketDef, facing};
+use crate::game::trajectory::Body;
+use crate::headless;
+use avian2d::prelude::{Collider, Mass};
+use bevy::prelude::*;
+use std::f32::consts::TAU;
+
+/// Duration of a single simulation step in seconds, the same as the fixed timestep of the game.
+pub const STEP: f32 = 1.0 / headless::STEPS_PER_SECOND;
+
+/// How long a plan is simulated.
+const HORIZON_SECS: f32 = 30.0;
+
+/// Number of directions tried for every burn.
+const DIRECTIONS: usize = 32;
+
+/// Burn durations tried for every burn, in seconds.
+const DURATIONS: [f32; 6] = [0.25, 0.5, 1.0, 1.5, 2.5, 4.0];
+
+/// How long the rocket coasts before the next burn.
+const COAST_SECS: [f32; 4] = [0.5, 2.0, 4.0, 8.0];
+
+const MAX_BURNS: usize = 3;
+
+/// Number of plans that are extended with another burn.
+const BEAM_WIDTH: usize = 6;
+
+/// Keeps some distance to the surface of planets, the rocket is not a point.
+const ROCKET_RADIUS: f32 = 32.0;
+
+/// Plans must get this far into the goal, to leave room for errors in the model.
+const GOAL_MARGIN: f32 = 0.75;
+
+/// Ranks plans that crashed behind plans that are still flying.
+const CRASH_PENALTY: f32 = 1000.0;
+
+/// The state of the rocket and the level to plan for.
+#[derive(Clone, Debug)]
+pub struct Problem {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub rotation: Quat,
+    /// Mass of the rocket, including the fuel if fuel has mass.
+    pub mass: f32,
+    /// Force of the engine of the burning stage.
+    pub thrust: f32,
+    /// Burn time left in the tank, in seconds.
+    pub fuel: f32,
+    /// Mass of one second worth of fuel of the burning stage.
+    pub fuel_mass: f32,
+    /// Turning time left in the RCS tank, in seconds.
+    pub rcs_fuel: f32,
+    pub rules: LevelRules,
+    pub bodies: Vec<Body>,
+    pub goal: Vec2,
+    pub goal_radius: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlannedBurn {
+    /// Seconds from now until the burn starts.
+    pub at: f32,
+    pub direction: Vec2,
+    /// Burn time in seconds.
+    pub duration: f32,
+}
+
+impl PlannedBurn {
+    fn end(&self) -> f32 {
+        self.at + self.duration
+    }
+}
+
+/// Burns that reach the goal.
+#[derive(Clone, Debug)]
+pub struct Plan {
+    pub burns: Vec<PlannedBurn>,
+    /// Burn time needed in seconds.
+    pub fuel: f32,
+    /// Seconds until the goal is reached.
+    pub arrival: f32,
+}
+
+/// What happens when flying a list of burns.
+#[derive(Clone, Copy, Debug)]
+pub struct Outcome {
+    /// Seconds until the goal was reached.
+    pub reached_at: Option<f32>,
+    pub crashed: bool,
+    /// Closest distance to the edge of the goal.
+    pub closest: f32,
+}
+
+impl Problem {
+    /// The start of a level, with the rocket the player gets.
+    /// Returns `None` if the level has no goal.
+    pub fn from_level(level: &LevelFile, config: &GameConfig) -> Option<Self> {
+        let goal = level.goal.as_ref()?;
+
+        let rocket = RocketDef::new(level.rules.rocket, &game::Assets::placeholder(0), config);
+        let stage = rocket.stages.first()?;
+        let mut mass = rocket.dry_mass + rocket.stages.iter().map(|s| s.mass).sum::<f32>();
+
+        if level.rules.fuel_has_mass {
+            mass += rocket
+                .stages
+                .iter()
+                .map(|s| s.fuel.as_secs() * s.fuel_mass)
+                .sum::<f32>();
+        }
+
+        let bodies = level
+            .planets
+            .iter()
+            .map(|planet| Body {
+                position: Vec2::from(planet.position),
+                mass: Mass::from_shape(&Collider::circle(planet.radius), config.planet_density).0,
+                radius: planet.radius,
+            })
+            .collect();
+
+        Some(Self {
+            position: Vec2::from(level.start),
+            velocity: Vec2::ZERO,
+            rotation: Quat::IDENTITY,
+            mass,
+            thrust: stage.thrust,
+            fuel: stage.fuel.as_secs(),
+            fuel_mass: stage.fuel_mass,
+            rcs_fuel: rocket.rcs_fuel.as_secs(),
+            rules: level.rules.clone(),
+            bodies,
+            goal: Vec2::from(goal.position),
+            goal_radius: goal.radius,
+        })
+    }
+
+    /// Simulates the burns until the goal is reached, the rocket crashed or the
+    /// horizon is over. Calls `on_step` with time, position and velocity after every step.
+    pub fn fly(&self, burns: &[PlannedBurn], mut on_step: impl FnMut(f32, Vec2, Vec2)) -> Outcome {
+        let mut position = self.position;
+        let mut velocity = self.velocity;
+        let mut rotation = self.rotation;
+        let mut rcs_fuel = self.rcs_fuel;
+        let mut burned = 0.0;
+
+        // direction and burn time left of the burn in progress
+        let mut current: Option<(Vec2, f32)> = None;
+        let mut upcoming = burns.iter().peekable();
+
+        let mut outcome = Outcome {
+            reached_at: None,
+            crashed: false,
+            closest: position.distance(self.goal) - self.goal_radius,
+        };
+
+        let steps = (HORIZON_SECS / STEP) as usize;
+
+        for step in 0..steps {
+            let time = step as f32 * STEP;
+
+            let mut force = Vec2::ZERO;
+
+            for body in &self.bodies {
+                force += attraction_force(body.position, body.mass, position);
+            }
+
+            while let Some(burn) = upcoming.next_if(|burn| burn.at <= time) {
+                current = Some((burn.direction, burn.duration));
+            }
+
+            if let Some((direction, remaining)) = &mut current {
+                let attitude = self.rules.attitude;
+
+                // turn as far as the rocket can this step, like `rotate_direction_of_thrust`
+                if let Some(turn_rate) = attitude.turn_rate_with_rcs(rcs_fuel > 0.0) {
+                    let max_step = turn_rate * STEP;
+                    let turn = facing(rotation)
+                        .angle_to(*direction)
+                        .clamp(-max_step, max_step);
+
+                    rotation = Quat::from_rotation_z(turn) * rotation;
+                    rcs_fuel = (rcs_fuel - STEP * turn.abs() / max_step).max(0.0);
+                }
+
+                // the burn only starts once the rocket faces into its direction
+                if attitude.is_facing(rotation, *direction) {
+                    force += match attitude {
+                        AttitudeControl::Free => *direction,
+                        AttitudeControl::Realistic { .. } => facing(rotation),
+                    } * self.thrust;
+
+                    *remaining -= STEP;
+                    burned += STEP;
+                }
+
+                if *remaining <= 0.0 {
+                    current = None;
+                }
+            }
+
+            let mut mass = self.mass;
+            if self.rules.fuel_has_mass {
+                mass -= burned * self.fuel_mass;
+            }
+
+            // semi implicit euler, just like the physics engine
+            velocity += force / mass * STEP;
+            position += velocity * STEP;
+
+            on_step(time + STEP, position, velocity);
+
+            let crashed = self
+                .bodies
+                .iter()
+                .any(|body| body.position.distance(position) < body.radius + ROCKET_RADIUS);
+
+            if crashed {
+                outcome.crashed = true;
+                break;
+            }
+
+            let distance = position.distance(self.goal);
+            outcome.closest = outcome.closest.min(distance - self.goal_radius);
+
+            if distance <= self.goal_radius * GOAL_MARGIN {
+                outcome.reached_at = Some(time + STEP);
+                break;
+            }
+        }
+
+        outcome
+    }
+
+    /// Searches for the burns that reach the goal with the least fuel.
+    pub fn solve(&self) -> Option<Plan> {
+        let mut best: Option<Plan> = None;
+        let mut beam = vec![Vec::new()];
+
+        for _ in 0..MAX_BURNS {
+            let mut candidates = Vec::new();
+
+            for burns in &beam {
+                for burn in self.next_burns(burns) {
+                    let mut burns = burns.clone();
+                    burns.push(burn);
+
+                    let fuel = fuel_of(&burns);
+                    let outcome = self.fly(&burns, |_, _, _| {});
+
+                    match outcome.reached_at {
+                        Some(arrival) => {
+                            let plan = Plan {
+                                burns,
+                                fuel,
+                                arrival,
+                            };
+
+                            if best.as_ref().is_none_or(|best| is_better(&plan, best)) {
+                                best = Some(plan);
+                            }
+                        }
+
+                        None => {
+                            let penalty = if outcome.crashed { CRASH_PENALTY } else { 0.0 };
+                            candidates.push((outcome.closest + penalty, burns));
+                        }
+                    }
+                }
+            }
+
+            candidates.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+            candidates.truncate(BEAM_WIDTH);
+
+            beam = candidates.into_iter().map(|(_, burns)| burns).collect();
+        }
+
+        best.map(|plan| self.trim(plan))
+    }
+
+    /// Every burn to try after the given ones.
+    fn next_burns(&self, burns: &[PlannedBurn]) -> Vec<PlannedBurn> {
+        let fuel_left = self.fuel - fuel_of(burns);
+
+        // the first burn starts right away, later ones after coasting
+        let starts: Vec<f32> = match burns.last() {
+            None => vec![0.0],
+            Some(last) => COAST_SECS.iter().map(|coast| last.end() + coast).collect(),
+        };
+
+        let mut next = Vec::new();
+
+        for at in starts {
+            for idx in 0..DIRECTIONS {
+                let direction = Vec2::from_angle(TAU * idx as f32 / DIRECTIONS as f32);
+
+                for duration in DURATIONS {
+                    if duration <= fuel_left {
+                        next.push(PlannedBurn {
+                            at,
+                            direction,
+                            duration,
+                        });
+                    }
+                }
+            }
+        }
+
+        next
+    }
+
+    /// Shortens the burns of a plan as long as it still reaches the goal.
+    /// Burns that get shorter than a single step are dropped.
+    fn trim(&self, mut plan: Plan) -> Plan {
+        let mut idx = 0;
+
+        while idx < plan.burns.len() {
+            let mut burns = plan.burns.clone();
+
+            let duration = burns[idx].duration * 0.9;
+            let dropped = duration < STEP;
+
+            if dropped {
+                burns.remove(idx);
+            } else {
+                burns[idx].duration = duration;
+            }
+
+            // later burns keep their distance to this one
+            let shift = if dropped { 0.0 } else { duration } - plan.burns[idx].duration;
+            let later = if dropped { idx } else { idx + 1 };
+            for burn in &mut burns[later..] {
+                burn.at += shift;
+            }
+
+            let Some(arrival) = self.fly(&burns, |_, _, _| {}).reached_at else {
+                // the burn is needed as it is, continue with the next one
+                idx += 1;
+                continue;
+            };
+
+            plan = Plan {
+                fuel: fuel_of(&burns),
+                burns,
+                arrival,
+            };
+        }
+
+        plan
+    }
+}
+
+fn fuel_of(burns: &[PlannedBurn]) -> f32 {
+    burns.iter().map(|burn| burn.duration).sum()
+}
+
+/// Less fuel is better, and arriving earlier if the fuel is the same.
+fn is_better(plan: &Plan, other: &Plan) -> bool {
+    (plan.fuel, plan.arrival) < (other.fuel, other.arrival)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::level_file::{GoalDef, PlanetDef};
+    use crate::testing::assert_near;
+
+    fn problem(planets: Vec<PlanetDef>) -> Problem {
+        let level = LevelFile {
+            start: [0.0, 0.0],
+            planets,
+            goal: Some(GoalDef {
+                position: [0.0, 800.0],
+                radius: 64.0,
+            }),
+            pickups: Vec::new(),
+            ..Default::default()
+        };
+
+        Problem::from_level(&level, &GameConfig::default()).unwrap()
+    }
+
+    #[test]
+    fn flies_straight_to_goal_in_empty_space() {
+        let problem = problem(Vec::new());
+        let plan = problem.solve().expect("goal is reachable");
+
+        // nothing pulls the rocket off course
+        for burn in &plan.burns {
+            assert!(burn.direction.y > 0.9);
+        }
+
+        let outcome = problem.fly(&plan.burns, |_, _, _| {});
+        assert_eq!(outcome.reached_at, Some(plan.arrival));
+    }
+
+    #[test]
+    fn flies_around_planet_in_the_way() {
+        let problem = problem(vec![PlanetDef {
+            position: [0.0, 400.0],
+            radius: 128.0,
+            assets: 0,
+            refuel: false,
+        }]);
+
+        let plan = problem.solve().expect("goal is reachable");
+        let outcome = problem.fly(&plan.burns, |_, _, _| {});
+
+        assert!(!outcome.crashed);
+        assert!(plan.fuel <= problem.fuel);
+    }
+
+    #[test]
+    fn trim_drops_burn_that_is_not_needed() {
+        let problem = problem(Vec::new());
+
+        let burn = |at, duration| PlannedBurn {
+            at,
+            direction: Vec2::Y,
+            duration,
+        };
+
+        // the second burn reaches the goal on its own
+        let burns = vec![burn(0.0, 0.25), burn(0.5, 2.5)];
+        let arrival = problem.fly(&burns, |_, _, _| {}).reached_at.unwrap();
+
+        let plan = problem.trim(Plan {
+            fuel: fuel_of(&burns),
+            burns,
+            arrival,
+        });
+
+        assert_eq!(plan.burns.len(), 1);
+
+        let outcome = problem.fly(&plan.burns, |_, _, _| {});
+        assert_eq!(outcome.reached_at, Some(plan.arrival));
+    }
+
+    #[test]
+    fn realistic_burn_waits_for_the_turn() {
+        let mut problem = problem(Vec::new());
+
+        let burns = [PlannedBurn {
+            at: 0.0,
+            direction: Vec2::X,
+            duration: 1.0,
+        }];
+
+        let speed_after = |problem: &Problem, secs: f32| {
+            let mut speed = 0.0;
+            problem.fly(&burns, |time, _, velocity| {
+                if time <= secs {
+                    speed = velocity.length();
+                }
+            });
+
+            speed
+        };
+
+        let free = speed_after(&problem, 1.0);
+
+        problem.rules.attitude = AttitudeControl::REALISTIC;
+        let realistic = speed_after(&problem, 1.0);
+
+        // the rocket faces up and has to turn a quarter before burning
+        assert!(realistic < free * 0.9);
+
+        // once turned, the burn lasts just as long
+        assert_near(speed_after(&problem, 2.0), free, free * 0.01);
+    }
+
+    #[test]
+    fn rocket_gets_lighter_if_fuel_has_mass() {
+        let mut problem = problem(Vec::new());
+        problem.rules.fuel_has_mass = true;
+
+        let burns = [PlannedBurn {
+            at: 0.0,
+            direction: Vec2::Y,
+            duration: 2.0,
+        }];
+
+        let mut speeds = Vec::new();
+        problem.fly(&burns, |time, _, velocity| {
+            if time == 1.0 || time == 2.0 {
+                speeds.push(velocity.y);
+            }
+        });
+
+        // the second second of the burn speeds up the lighter rocket more
+        assert!(speeds[1] - speeds[0] > speeds[0]);
+    }
+
+    #[test]
+    fn level_without_goal_has_no_problem() {
+        let level = LevelFile::tutorial();
+        assert!(Problem::from_level(&level, &GameConfig::default()).is_none());
+    }
+}
@@ -1,31 +1,310 @@
+use crate::game::config::GameConfig;
+use crate::game::input::InputActive;
+use crate::game::planet::Planet;
 use crate::game::player::Player;
+use crate::menus::Menu;
+use crate::screens::Screen;
 use crate::{AppSystems, MainCamera};
 use avian2d::prelude::LinearVelocity;
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
 use bevy::math::ops::ln;
 use bevy::prelude::*;
+use fastnoise_lite::FastNoiseLite;
+
+/// Limits of the zoom the player can choose by scrolling or pinching.
+const USER_ZOOM_MIN: f32 = 0.5;
+const USER_ZOOM_MAX: f32 = 2.0;
+
+/// Limit of the automatic zoom out.
+const AUTO_ZOOM_MAX: f32 = 4.0;
+
+/// Speed at which the camera is zoomed out to twice the default width.
+const AUTO_ZOOM_SPEED: f32 = 800.0;
+
+/// Half the width of the visible world at a zoom level of one.
+const HALF_VIEW_WIDTH: f32 = 512.0;
+
+/// Maximum distance of the free look from the rocket at a zoom level of one.
+const FREE_LOOK_MAX: f32 = 768.0;
+
+/// Speed of the free look in world units per second of real time.
+const FREE_LOOK_SPEED: f32 = 768.0;
+
+/// Offset and rotation of the camera at full trauma.
+const SHAKE_MAX_OFFSET: f32 = 32.0;
+const SHAKE_MAX_ANGLE: f32 = 3.0_f32.to_radians();
+
+/// How fast the camera shakes.
+const SHAKE_FREQUENCY: f32 = 15.0;
+
+/// Trauma removed per second.
+const TRAUMA_DECAY: f32 = 1.5;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(Update, follow_player.in_set(AppSystems::UpdateCamera));
+    app.init_resource::<CameraControl>();
+    app.init_resource::<CameraTrauma>();
+    app.init_resource::<ScreenShake>();
+
+    app.add_systems(OnEnter(Screen::Gameplay), reset_camera_control);
+    app.add_systems(OnExit(Screen::Gameplay), reset_camera_zoom);
+
+    app.add_systems(
+        Update,
+        (zoom_on_scroll, zoom_on_pinch, free_look_while_aiming)
+            .run_if(in_state(Screen::Gameplay).and(in_state(Menu::None)))
+            .in_set(AppSystems::RecordInput),
+    );
+
+    app.add_systems(
+        Update,
+        (
+            follow_player,
+            frame_camera,
+            apply_camera_shake.after(follow_player),
+        )
+            .in_set(AppSystems::UpdateCamera),
+    );
+}
+
+/// State of the main camera that is controlled by the player.
+#[derive(Resource)]
+pub struct CameraControl {
+    /// Zoom chosen by the player, multiplied with the automatic zoom.
+    pub zoom: f32,
+    /// Offset of the camera while the player is looking around.
+    free_look: Vec2,
+    /// The point the camera follows, without the free look.
+    anchor: Option<Vec2>,
+}
+
+impl Default for CameraControl {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            free_look: Vec2::ZERO,
+            anchor: None,
+        }
+    }
+}
+
+/// How much the camera is shaking right now, between zero and one.
+/// Decays over time.
+#[derive(Resource, Default)]
+pub struct CameraTrauma(f32);
+
+impl CameraTrauma {
+    pub fn add(&mut self, amount: f32) {
+        self.0 = (self.0 + amount).clamp(0.0, 1.0);
+    }
+}
+
+/// Accessibility setting, turns off all camera shake if disabled.
+#[derive(Resource)]
+pub struct ScreenShake {
+    pub enabled: bool,
+}
+
+impl Default for ScreenShake {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+fn reset_camera_control(mut control: ResMut<CameraControl>, mut trauma: ResMut<CameraTrauma>) {
+    *control = CameraControl::default();
+    trauma.0 = 0.0;
+}
+
+fn reset_camera_zoom(mut projection: Single<&mut Projection, With<MainCamera>>) {
+    if let Projection::Orthographic(projection) = projection.as_mut() {
+        projection.scale = 1.0;
+    }
+}
+
+fn zoom_on_scroll(mut control: ResMut<CameraControl>, mut wheel: EventReader<MouseWheel>) {
+    for event in wheel.read() {
+        let steps = match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / 100.0,
+        };
+
+        // scrolling up zooms in
+        control.zoom = (control.zoom * 0.9_f32.powf(steps)).clamp(USER_ZOOM_MIN, USER_ZOOM_MAX);
+    }
+}
+
+fn zoom_on_pinch(mut control: ResMut<CameraControl>, touches: Res<Touches>) {
+    let mut pressed = touches.iter();
+
+    let (Some(first), Some(second), None) = (pressed.next(), pressed.next(), pressed.next()) else {
+        return;
+    };
+
+    let previous = first
+        .previous_position()
+        .distance(second.previous_position());
+    let current = first.position().distance(second.position());
+
+    if previous <= 0.0 || current <= 0.0 {
+        return;
+    }
+
+    // spreading the fingers zooms in
+    control.zoom = (control.zoom * previous / current).clamp(USER_ZOOM_MIN, USER_ZOOM_MAX);
+}
+
+fn free_look_while_aiming(
+    mut control: ResMut<CameraControl>,
+    aiming: Query<(), (With<Player>, With<InputActive>)>,
+    keys: Res<ButtonInput<KeyCode>>,
+    time: Res<Time<Real>>,
+) {
+    if aiming.is_empty() {
+        // the camera falls back to the rocket once the player stopped aiming
+        control
+            .free_look
+            .smooth_nudge(&Vec2::ZERO, 4.0, time.delta_secs());
+
+        if control.free_look.length_squared() < 1.0 {
+            control.free_look = Vec2::ZERO;
+        }
+
+        return;
+    }
+
+    let mut direction = Vec2::ZERO;
+
+    if keys.any_pressed([KeyCode::KeyA, KeyCode::ArrowLeft]) {
+        direction.x -= 1.0;
+    }
+
+    if keys.any_pressed([KeyCode::KeyD, KeyCode::ArrowRight]) {
+        direction.x += 1.0;
+    }
+
+    if keys.any_pressed([KeyCode::KeyS, KeyCode::ArrowDown]) {
+        direction.y -= 1.0;
+    }
+
+    if keys.any_pressed([KeyCode::KeyW, KeyCode::ArrowUp]) {
+        direction.y += 1.0;
+    }
+
+    let max = FREE_LOOK_MAX * control.zoom;
+    let free_look =
+        control.free_look + direction.normalize_or_zero() * FREE_LOOK_SPEED * time.delta_secs();
+
+    control.free_look = free_look.clamp_length_max(max);
 }
 
 fn follow_player(
     mut camera: Single<&mut Transform, With<MainCamera>>,
+    mut control: ResMut<CameraControl>,
     time: Res<Time>,
+    config: Res<GameConfig>,
     query_player: Query<(&Transform, &LinearVelocity), (With<Player>, Without<MainCamera>)>,
 ) {
     let Ok((player_transform, player_velocity)) = query_player.single() else {
         return;
     };
 
-    let mut current = camera.translation.xy();
+    let mut current = control.anchor.unwrap_or(camera.translation.xy());
 
     // target the position the player might be soon
-    let offset = (player_velocity.0 * 4.0).clamp_length_max(512.0);
+    let offset =
+        (player_velocity.0 * config.camera_lead_secs).clamp_length_max(config.camera_lead_max);
     let target = player_transform.translation.xy() + offset;
 
     // nudge the position a little
     current.smooth_nudge(&target, ln(5.0), time.delta_secs());
+    control.anchor = Some(current);
+
+    let current = current + control.free_look;
 
     camera.translation.x = current.x;
     camera.translation.y = current.y;
 }
+
+/// Zooms out when the rocket is fast or far away from all planets,
+/// so the nearest planet stays in frame.
+fn frame_camera(
+    mut projection: Single<&mut Projection, With<MainCamera>>,
+    control: Res<CameraControl>,
+    time: Res<Time<Real>>,
+    player: Query<(&Transform, &LinearVelocity, Has<InputActive>), With<Player>>,
+    planets: Query<(&Transform, &Planet)>,
+) {
+    let Projection::Orthographic(projection) = projection.as_mut() else {
+        return;
+    };
+
+    let Ok((player_transform, velocity, aiming)) = player.single() else {
+        return;
+    };
+
+    if aiming {
+        // keep the scale while aiming, the length of the input depends on it
+        return;
+    }
+
+    let position = player_transform.translation.xy();
+
+    let speed_zoom = 1.0 + velocity.length() / AUTO_ZOOM_SPEED;
+
+    // keep the near side of the nearest planet well within the frame
+    let planet_zoom = planets
+        .iter()
+        .map(|(transform, planet)| {
+            let distance = transform.translation.xy().distance(position);
+            (distance - 0.5 * planet.radius) / (0.8 * HALF_VIEW_WIDTH)
+        })
+        .min_by(f32::total_cmp)
+        .unwrap_or(1.0);
+
+    let auto_zoom = speed_zoom.max(planet_zoom).clamp(1.0, AUTO_ZOOM_MAX);
+    let target = auto_zoom * control.zoom;
+
+    projection
+        .scale
+        .smooth_nudge(&target, ln(3.0), time.delta_secs());
+}
+
+/// The noise the camera shake follows.
+struct ShakeNoise(FastNoiseLite);
+
+impl Default for ShakeNoise {
+    fn default() -> Self {
+        let mut noise = FastNoiseLite::with_seed(0);
+        noise.frequency = SHAKE_FREQUENCY;
+        Self(noise)
+    }
+}
+
+/// Shakes the camera on top of the position chosen by `follow_player`.
+fn apply_camera_shake(
+    mut camera: Single<&mut Transform, With<MainCamera>>,
+    mut trauma: ResMut<CameraTrauma>,
+    settings: Res<ScreenShake>,
+    time: Res<Time<Real>>,
+    noise: Local<ShakeNoise>,
+) {
+    trauma.0 = (trauma.0 - TRAUMA_DECAY * time.delta_secs()).max(0.0);
+
+    if !settings.enabled || trauma.0 <= 0.0 {
+        trauma.0 = 0.0;
+        camera.rotation = Quat::IDENTITY;
+        return;
+    }
+
+    // squaring makes small amounts of trauma barely noticeable
+    let shake = trauma.0 * trauma.0;
+
+    let noise = &noise.0;
+    let t = time.elapsed_secs();
+    let offset = vec2(noise.get_noise_2d(t, 0.0), noise.get_noise_2d(t, 10.0));
+    let angle = noise.get_noise_2d(t, 20.0);
+
+    camera.translation.x += offset.x * SHAKE_MAX_OFFSET * shake;
+    camera.translation.y += offset.y * SHAKE_MAX_OFFSET * shake;
+    camera.rotation = Quat::from_rotation_z(angle * SHAKE_MAX_ANGLE * shake);
+}
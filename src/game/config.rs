@@ -0,0 +1,236 @@
+//! Gameplay tuning loaded from `assets/config/game.config.ron`.
+//!
+//! The file is watched in native dev builds, so values can be tuned while the
+//! game is running. Until the file is loaded, the defaults below are used.
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::time::Duration;
+
+pub const CONFIG_PATH: &str = "config/game.config.ron";
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<GameConfig>();
+    app.init_asset_loader::<GameConfigLoader>();
+    app.init_resource::<GameConfig>();
+
+    app.add_systems(Startup, load_game_config);
+    app.add_systems(PreUpdate, apply_game_config);
+}
+
+#[derive(Asset, Resource, TypePath, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct GameConfig {
+    /// The stages of the rocket, starting with the one that burns first.
+    pub stages: Vec<StageConfig>,
+    /// Fuel for turning the rocket in seconds. Applied on the next level start.
+    pub rcs_fuel_secs: f32,
+    /// Length of the aiming line in world units per second of burn time.
+    pub aim_length_per_second: f32,
+    /// Density of planets, the mass of a planet follows from this and its radius.
+    pub planet_density: f32,
+    /// How many seconds the camera looks ahead of the player.
+    pub camera_lead_secs: f32,
+    /// Maximum distance the camera looks ahead of the player.
+    pub camera_lead_max: f32,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct StageConfig {
+    /// Force applied while the stage is burning.
+    pub thrust: f32,
+    /// Fuel in seconds of burn time. Applied on the next level start.
+    pub fuel_secs: f32,
+}
+
+impl Default for StageConfig {
+    fn default() -> Self {
+        Self {
+            thrust: 100_000.0,
+            fuel_secs: 10.0,
+        }
+    }
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            stages: vec![StageConfig::default(); 2],
+            rcs_fuel_secs: 5.0,
+            aim_length_per_second: 100.0,
+            planet_density: 100_000.0,
+            camera_lead_secs: 4.0,
+            camera_lead_max: 512.0,
+        }
+    }
+}
+
+impl GameConfig {
+    /// The config of the stage with the given index, or the default
+    /// if the config describes fewer stages than the rocket has.
+    pub fn stage(&self, idx: usize) -> StageConfig {
+        self.stages.get(idx).copied().unwrap_or_default()
+    }
+
+    /// Rejects values the game can not work with, e.g. negative durations.
+    pub fn validate(&self) -> Result<(), String> {
+        for (idx, stage) in self.stages.iter().enumerate() {
+            check_non_negative(&format!("stages[{idx}].thrust"), stage.thrust)?;
+            check_secs(&format!("stages[{idx}].fuel_secs"), stage.fuel_secs)?;
+        }
+
+        check_secs("rcs_fuel_secs", self.rcs_fuel_secs)?;
+
+        // the aiming line is divided by this to get the burn time
+        check_positive("aim_length_per_second", self.aim_length_per_second)?;
+        check_positive("planet_density", self.planet_density)?;
+
+        // zero disables the look ahead
+        check_non_negative("camera_lead_secs", self.camera_lead_secs)?;
+        check_non_negative("camera_lead_max", self.camera_lead_max)
+    }
+}
+
+fn check_positive(name: &str, value: f32) -> Result<(), String> {
+    if value.is_finite() && value > 0.0 {
+        Ok(())
+    } else {
+        Err(format!("{name} must be a positive number, got {value}"))
+    }
+}
+
+fn check_non_negative(name: &str, value: f32) -> Result<(), String> {
+    if value.is_finite() && value >= 0.0 {
+        Ok(())
+    } else {
+        Err(format!("{name} must be a non-negative number, got {value}"))
+    }
+}
+
+fn check_secs(name: &str, secs: f32) -> Result<(), String> {
+    match Duration::try_from_secs_f32(secs) {
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!(
+            "{name} must be a non-negative number of seconds, got {secs}"
+        )),
+    }
+}
+
+/// Keeps the config asset alive, so it is reloaded when the file changes.
+#[derive(Resource)]
+struct GameConfigHandle(Handle<GameConfig>);
+
+#[derive(Default, TypePath)]
+struct GameConfigLoader;
+
+impl AssetLoader for GameConfigLoader {
+    type Asset = GameConfig;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<GameConfig, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let config: GameConfig = ron::de::from_bytes(&bytes)?;
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["config.ron"]
+    }
+}
+
+fn load_game_config(mut commands: Commands, server: Res<AssetServer>) {
+    commands.insert_resource(GameConfigHandle(server.load(CONFIG_PATH)));
+}
+
+/// Copies the config into the resource whenever the file was (re)loaded.
+fn apply_game_config(
+    mut events: EventReader<AssetEvent<GameConfig>>,
+    handle: Res<GameConfigHandle>,
+    configs: Res<Assets<GameConfig>>,
+    mut config: ResMut<GameConfig>,
+) {
+    for event in events.read() {
+        if !event.is_loaded_with_dependencies(&handle.0) && !event.is_modified(&handle.0) {
+            continue;
+        }
+
+        if let Some(loaded) = configs.get(&handle.0) {
+            info!("Applying game config: {:?}", loaded);
+            *config = loaded.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checked_in_config_is_valid() {
+        let source = include_str!("../../assets/config/game.config.ron");
+        let config: GameConfig = ron::from_str(source).unwrap();
+
+        assert_eq!(config.validate(), Ok(()));
+    }
+
+    #[test]
+    fn rejects_invalid_fuel() {
+        for fuel_secs in [-1.0, f32::INFINITY, f32::NAN, f32::MAX] {
+            let config = GameConfig {
+                stages: vec![StageConfig {
+                    thrust: 1.0,
+                    fuel_secs,
+                }],
+                ..default()
+            };
+
+            assert!(config.validate().is_err(), "accepted {fuel_secs}");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_tuning() {
+        let invalid: [fn(&mut GameConfig, f32); 4] = [
+            |config, value| config.aim_length_per_second = value,
+            |config, value| config.planet_density = value,
+            |config, value| config.camera_lead_secs = value,
+            |config, value| config.camera_lead_max = value,
+        ];
+
+        for (idx, set) in invalid.iter().enumerate() {
+            for value in [-1.0, f32::INFINITY, f32::NAN] {
+                let mut config = GameConfig::default();
+                set(&mut config, value);
+
+                assert!(config.validate().is_err(), "accepted {value} for #{idx}");
+            }
+        }
+
+        // the aiming line and the mass of planets can not be zero
+        let config = GameConfig {
+            aim_length_per_second: 0.0,
+            ..default()
+        };
+
+        assert!(config.validate().is_err());
+
+        let config = GameConfig {
+            planet_density: 0.0,
+            ..default()
+        };
+
+        assert!(config.validate().is_err());
+    }
+}
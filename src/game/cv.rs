@@ -3,6 +3,22 @@ use bevy::prelude::Color;
 
 pub const COLOR_BACKGROUND: Color = srgb_from_u32(0x553683ff);
 pub const COLOR_THRUST_INPUT_LINE: Color = srgb_from_u32(0xdfb2d9ff);
+pub const COLOR_GOAL: Color = srgb_from_u32(0xffd966ff);
+pub const COLOR_PICKUP: Color = srgb_from_u32(0x9fe2bfff);
+pub const COLOR_NAV_PLANET: Color = srgb_from_u32(0xdfb2d9ff);
+pub const COLOR_PREDICTED_PATH: Color = srgb_from_u32(0xffffff80);
+pub const COLOR_MAP_BACKGROUND: Color = srgb_from_u32(0x2e1d47ff);
+pub const COLOR_MAP_PLAYER: Color = srgb_from_u32(0xffffffff);
+pub const COLOR_DUST: Color = srgb_from_u32(0xc9b8a6c0);
+pub const COLOR_DEBRIS: Color = srgb_from_u32(0xe8e8e8ff);
+pub const COLOR_EXHAUST_HOT: Color = srgb_from_u32(0xfff3b0ff);
+pub const COLOR_EXHAUST_COLD: Color = srgb_from_u32(0xe05a4780);
+pub const COLOR_TRAIL_BURN: Color = srgb_from_u32(0xf7a072c0);
+pub const COLOR_TRAIL_COAST: Color = srgb_from_u32(0xdfb2d980);
+pub const COLOR_TERMINATOR: Color = srgb_from_u32(0x1e1030b0);
+pub const COLOR_EDITOR_SELECTION: Color = srgb_from_u32(0xffffffc0);
+pub const COLOR_EDITOR_REFUEL: Color = srgb_from_u32(0x9fe2bfc0);
+pub const COLOR_HINT: Color = srgb_from_u32(0x9fe2bfc0);
 
 const fn srgb_from_u32(color: u32) -> Color {
     let r = ((color >> 24) & 0xff) as f32 / 255.0;
@@ -15,11 +31,24 @@ const fn srgb_from_u32(color: u32) -> Color {
 
 pub const LAYER_ROCKET: Layer = Layer(1.0);
 pub const LAYER_OFFSET_ROCKET_FIN_BG: Layer = Layer(-0.6);
-pub const LAYER_OFFSET_ROCKET_PLUME: Layer = Layer(-0.5);
 pub const LAYER_OFFSET_ROCKET_FIN_FG: Layer = Layer(0.1);
+pub const LAYER_OFFSET_ROCKET_STAGE: Layer = Layer(-0.2);
+
+pub const LAYER_DEBRIS: Layer = Layer(0.5);
+
+pub const LAYER_PARTICLES: Layer = Layer(0.6);
 
 pub const LAYER_STARS: Layer = Layer(-2.0);
 
 pub const LAYER_PLAYER_INPUT: Layer = Layer(2.0);
 
+pub const LAYER_TRAIL: Layer = Layer(0.7);
+
+pub const LAYER_MAP_ICONS: Layer = Layer(3.0);
+
+pub const LAYER_GOALS: Layer = Layer(-0.5);
+
 pub const LAYER_PLANETS: Layer = Layer(-1.0);
+
+// above all layers of a planet
+pub const LAYER_PLANET_TERMINATOR: Layer = LAYER_PLANETS.offset_by(8);
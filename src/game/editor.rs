@@ -0,0 +1,734 @@
+//! An in-game level editor, only available in dev builds.
+//!
+//! Press F2 during gameplay to open the current level in the editor. Planets,
+//! the start position, the goal and pickups are selected and dragged using
+//! `bevy_picking`. Press F5 to playtest the level and F2 to get back.
+
+use crate::common::cursor::WorldCursor;
+use crate::game::cv::{
+    COLOR_EDITOR_REFUEL, COLOR_EDITOR_SELECTION, COLOR_GOAL, COLOR_PICKUP, LAYER_GOALS,
+    LAYER_MAP_ICONS, LAYER_ROCKET,
+};
+use crate::game::level::{CustomLevel, Level, LevelRules, level_file};
+use crate::game::level_file::{CUSTOM_LEVEL_PATH, GoalDef, LevelFile, PickupDef, PlanetDef};
+use crate::game::light::LightSource;
+use crate::game::planet;
+use crate::game::rocket::{AttitudeControl, RocketKind};
+use crate::screens::Screen;
+use crate::ui::widget;
+use crate::{MainCamera, game};
+use bevy::ecs::system::SystemParam;
+use bevy::input::common_conditions::input_just_pressed;
+use bevy::input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use bevy::ui::Val::*;
+
+const PLANET_RADIUS_DEFAULT: f32 = 96.0;
+const PLANET_RADIUS_MIN: f32 = 32.0;
+const PLANET_RADIUS_MAX: f32 = 1024.0;
+
+const GOAL_RADIUS_DEFAULT: f32 = 64.0;
+const PICKUP_RADIUS: f32 = 32.0;
+const PICKUP_FUEL_SECS: u64 = 5;
+
+/// Size of the start marker, roughly the size of the rocket.
+const START_RADIUS: f32 = 48.0;
+
+const PAN_SPEED: f32 = 1024.0;
+
+/// Either of these held down turns S and L into save and load.
+const MODIFIER_KEYS: [KeyCode; 4] = [
+    KeyCode::ControlLeft,
+    KeyCode::ControlRight,
+    KeyCode::SuperLeft,
+    KeyCode::SuperRight,
+];
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<CustomLevel>();
+    app.init_resource::<EditorSelection>();
+    app.init_resource::<EditorRules>();
+    app.init_resource::<EditorLight>();
+
+    app.add_systems(
+        Update,
+        open_editor.run_if(in_state(Screen::Gameplay).and(input_just_pressed(KeyCode::F2))),
+    );
+
+    app.add_systems(
+        OnEnter(Screen::Editor),
+        (spawn_editor_level, spawn_editor_ui),
+    );
+
+    app.add_systems(
+        Update,
+        (
+            pan_and_zoom,
+            place_planet.run_if(input_just_pressed(KeyCode::KeyN)),
+            place_goal.run_if(input_just_pressed(KeyCode::KeyG)),
+            place_pickup.run_if(input_just_pressed(KeyCode::KeyK)),
+            delete_selected.run_if(
+                input_just_pressed(KeyCode::Delete).or(input_just_pressed(KeyCode::Backspace)),
+            ),
+            clear_selection.run_if(input_just_pressed(KeyCode::Escape)),
+            edit_selected_planet,
+            toggle_fuel_mass.run_if(input_just_pressed(KeyCode::KeyM)),
+            toggle_attitude_control.run_if(input_just_pressed(KeyCode::KeyT)),
+            toggle_rocket.run_if(input_just_pressed(KeyCode::KeyB)),
+            save_level,
+            load_level,
+            start_playtest.run_if(input_just_pressed(KeyCode::F5)),
+            update_resize_handle,
+            draw_editor_gizmos,
+            update_status_text,
+        )
+            .run_if(in_state(Screen::Editor)),
+    );
+}
+
+/// The currently selected entity in the editor.
+#[derive(Resource, Default)]
+struct EditorSelection(Option<Entity>);
+
+/// The rules of the edited level, which have no entity to be edited through.
+#[derive(Resource, Default)]
+struct EditorRules(LevelRules);
+
+/// The light of the edited level, which is kept as it was loaded.
+#[derive(Resource, Default)]
+struct EditorLight(LightSource);
+
+/// Marks everything that can be selected and dragged.
+#[derive(Component)]
+struct Editable;
+
+#[derive(Component)]
+struct EditorPlanet {
+    assets: usize,
+    radius: f32,
+    /// The radius the planet was spawned with, it is scaled to match `radius`.
+    spawned_radius: f32,
+    refuel: bool,
+}
+
+#[derive(Component)]
+struct EditorStart;
+
+#[derive(Component)]
+struct EditorGoal {
+    radius: f32,
+}
+
+#[derive(Component)]
+struct EditorPickup {
+    fuel_secs: u64,
+}
+
+/// Offset between the cursor and the dragged entity.
+#[derive(Component)]
+struct DragOffset(Vec2);
+
+/// Dragging this changes the radius of the selected planet.
+#[derive(Component)]
+struct ResizeHandle;
+
+#[derive(Component)]
+struct StatusText;
+
+fn open_editor(
+    mut level: ResMut<Level>,
+    mut custom: ResMut<CustomLevel>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    // built-in levels are copied, so the editor never changes them
+    if *level != Level::Custom {
+        custom.0 = level_file(*level, None);
+        *level = Level::Custom;
+    }
+
+    next_screen.set(Screen::Editor);
+}
+
+fn spawn_editor_level(
+    mut commands: Commands,
+    mut selection: ResMut<EditorSelection>,
+    assets: Res<game::Assets>,
+    custom: Res<CustomLevel>,
+) {
+    selection.0 = None;
+    spawn_level_entities(&mut commands, &assets, &custom.0);
+
+    commands
+        .spawn((
+            Name::new("ResizeHandle"),
+            StateScoped(Screen::Editor),
+            ResizeHandle,
+            LAYER_MAP_ICONS,
+            Sprite::from_color(COLOR_EDITOR_SELECTION, Vec2::splat(24.0)),
+            Visibility::Hidden,
+        ))
+        .observe(resize_planet);
+}
+
+fn spawn_level_entities(commands: &mut Commands, assets: &game::Assets, file: &LevelFile) {
+    commands.insert_resource(EditorRules(file.rules.clone()));
+    commands.insert_resource(EditorLight(file.light));
+
+    // shadows in the editor fall like they will in the level
+    commands.insert_resource(file.light);
+
+    let start = Vec2::from(file.start);
+
+    spawn_editable(
+        commands,
+        (
+            Name::new("EditorStart"),
+            EditorStart,
+            Transform::from_translation(start.extend(LAYER_ROCKET.0)),
+            Sprite::from_image(assets.rocket_base.clone()),
+        ),
+    );
+
+    for def in &file.planets {
+        spawn_planet(commands, assets, def);
+    }
+
+    if let Some(goal) = &file.goal {
+        spawn_goal(commands, assets, goal);
+    }
+
+    for pickup in &file.pickups {
+        spawn_pickup(commands, assets, pickup);
+    }
+}
+
+fn spawn_editable(commands: &mut Commands, bundle: impl Bundle) -> Entity {
+    commands
+        .spawn((StateScoped(Screen::Editor), Editable, bundle))
+        .observe(select_on_press)
+        .observe(start_drag)
+        .observe(drag)
+        .id()
+}
+
+fn spawn_planet(commands: &mut Commands, assets: &game::Assets, def: &PlanetDef) -> Entity {
+    let index = def.assets.min(assets.planets.len() - 1);
+
+    spawn_editable(
+        commands,
+        (
+            Name::new("EditorPlanet"),
+            EditorPlanet {
+                assets: index,
+                radius: def.radius,
+                spawned_radius: def.radius,
+                refuel: def.refuel,
+            },
+            Transform::from_translation(Vec2::from(def.position).extend(0.0)),
+            planet::bundle(&assets.planets[index], def.radius),
+        ),
+    )
+}
+
+fn spawn_goal(commands: &mut Commands, assets: &game::Assets, def: &GoalDef) -> Entity {
+    spawn_editable(
+        commands,
+        (
+            Name::new("EditorGoal"),
+            EditorGoal { radius: def.radius },
+            Transform::from_translation(Vec2::from(def.position).extend(LAYER_GOALS.0)),
+            Sprite {
+                image: assets.star_large.clone(),
+                color: COLOR_GOAL,
+                custom_size: Some(Vec2::splat(2.0 * def.radius)),
+                ..default()
+            },
+        ),
+    )
+}
+
+fn spawn_pickup(commands: &mut Commands, assets: &game::Assets, def: &PickupDef) -> Entity {
+    spawn_editable(
+        commands,
+        (
+            Name::new("EditorPickup"),
+            EditorPickup {
+                fuel_secs: def.fuel_secs,
+            },
+            Transform::from_translation(Vec2::from(def.position).extend(LAYER_GOALS.0)),
+            Sprite {
+                image: assets.star_small.clone(),
+                color: COLOR_PICKUP,
+                custom_size: Some(Vec2::splat(2.0 * PICKUP_RADIUS)),
+                ..default()
+            },
+        ),
+    )
+}
+
+fn spawn_editor_ui(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Editor Help"),
+        StateScoped(Screen::Editor),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Px(16.0),
+            top: Px(16.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Px(8.0),
+            ..default()
+        },
+        Pickable::IGNORE,
+        children![
+            widget::label(
+                "Drag to move, drag the handle to resize.\n\
+                N: planet, G: goal, K: pickup, Del: delete\n\
+                [ ]: planet look, R: refuel station\n\
+                M: fuel has mass, T: realistic turning, B: rocket\n\
+                WASD / right drag: pan, scroll: zoom\n\
+                Ctrl+S: save, Ctrl+L: load, F5: playtest"
+            ),
+            (StatusText, widget::label("")),
+        ],
+    ));
+}
+
+/// Everything needed to turn the editor entities back into a level.
+#[derive(SystemParam)]
+struct EditedLevel<'w, 's> {
+    start: Query<'w, 's, &'static Transform, With<EditorStart>>,
+    planets: Query<'w, 's, (&'static Transform, &'static EditorPlanet)>,
+    goals: Query<'w, 's, (&'static Transform, &'static EditorGoal)>,
+    pickups: Query<'w, 's, (&'static Transform, &'static EditorPickup)>,
+    rules: Res<'w, EditorRules>,
+    light: Res<'w, EditorLight>,
+}
+
+impl EditedLevel<'_, '_> {
+    fn collect(&self) -> LevelFile {
+        let position = |transform: &Transform| transform.translation.xy().to_array();
+
+        LevelFile {
+            start: self.start.iter().next().map(position).unwrap_or_default(),
+            planets: self
+                .planets
+                .iter()
+                .map(|(transform, planet)| PlanetDef {
+                    position: position(transform),
+                    radius: planet.radius,
+                    assets: planet.assets,
+                    refuel: planet.refuel,
+                })
+                .collect(),
+            goal: self.goals.iter().next().map(|(transform, goal)| GoalDef {
+                position: position(transform),
+                radius: goal.radius,
+            }),
+            pickups: self
+                .pickups
+                .iter()
+                .map(|(transform, pickup)| PickupDef {
+                    position: position(transform),
+                    fuel_secs: pickup.fuel_secs,
+                })
+                .collect(),
+            rules: self.rules.0.clone(),
+            light: self.light.0,
+        }
+    }
+}
+
+fn select_on_press(trigger: Trigger<Pointer<Pressed>>, mut selection: ResMut<EditorSelection>) {
+    if trigger.button == PointerButton::Primary {
+        selection.0 = Some(trigger.target());
+    }
+}
+
+fn start_drag(
+    trigger: Trigger<Pointer<DragStart>>,
+    mut commands: Commands,
+    cursor: Res<WorldCursor>,
+    transforms: Query<&Transform>,
+) -> Result {
+    if trigger.button != PointerButton::Primary {
+        return Ok(());
+    }
+
+    let transform = transforms.get(trigger.target())?;
+    let offset = transform.translation.xy() - cursor.0;
+    commands.entity(trigger.target()).insert(DragOffset(offset));
+
+    Ok(())
+}
+
+fn drag(
+    trigger: Trigger<Pointer<Drag>>,
+    cursor: Res<WorldCursor>,
+    mut dragged: Query<(&mut Transform, &DragOffset)>,
+) {
+    let Ok((mut transform, offset)) = dragged.get_mut(trigger.target()) else {
+        return;
+    };
+
+    let position = cursor.0 + offset.0;
+    transform.translation.x = position.x;
+    transform.translation.y = position.y;
+}
+
+fn resize_planet(
+    trigger: Trigger<Pointer<Drag>>,
+    cursor: Res<WorldCursor>,
+    selection: Res<EditorSelection>,
+    mut planets: Query<(&mut Transform, &mut EditorPlanet)>,
+) {
+    if trigger.button != PointerButton::Primary {
+        return;
+    }
+
+    let Some(Ok((mut transform, mut planet))) = selection.0.map(|entity| planets.get_mut(entity))
+    else {
+        return;
+    };
+
+    planet.radius = cursor
+        .0
+        .distance(transform.translation.xy())
+        .clamp(PLANET_RADIUS_MIN, PLANET_RADIUS_MAX);
+
+    transform.scale = Vec3::splat(planet.radius / planet.spawned_radius);
+}
+
+fn update_resize_handle(
+    selection: Res<EditorSelection>,
+    planets: Query<(&Transform, &EditorPlanet)>,
+    handle: Single<(&mut Transform, &mut Visibility), (With<ResizeHandle>, Without<EditorPlanet>)>,
+) {
+    let (mut transform, mut visibility) = handle.into_inner();
+
+    let Some(Ok((planet_transform, planet))) = selection.0.map(|entity| planets.get(entity)) else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    let position = planet_transform.translation.xy() + vec2(planet.radius, 0.0);
+    transform.translation.x = position.x;
+    transform.translation.y = position.y;
+    *visibility = Visibility::Inherited;
+}
+
+fn pan_and_zoom(
+    time: Res<Time<Real>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    mut motion: EventReader<MouseMotion>,
+    mut wheel: EventReader<MouseWheel>,
+    camera: Single<(&mut Transform, &mut Projection), With<MainCamera>>,
+) {
+    let (mut transform, mut projection) = camera.into_inner();
+
+    let Projection::Orthographic(projection) = &mut *projection else {
+        return;
+    };
+
+    for event in wheel.read() {
+        let steps = match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / 100.0,
+        };
+
+        // scrolling up zooms in
+        projection.scale = (projection.scale * 0.9_f32.powf(steps)).clamp(0.25, 16.0);
+    }
+
+    let mut direction = Vec2::ZERO;
+
+    for (key, step) in [
+        (KeyCode::KeyW, Vec2::Y),
+        (KeyCode::ArrowUp, Vec2::Y),
+        (KeyCode::KeyS, Vec2::NEG_Y),
+        (KeyCode::ArrowDown, Vec2::NEG_Y),
+        (KeyCode::KeyA, Vec2::NEG_X),
+        (KeyCode::ArrowLeft, Vec2::NEG_X),
+        (KeyCode::KeyD, Vec2::X),
+        (KeyCode::ArrowRight, Vec2::X),
+    ] {
+        if keys.pressed(key) {
+            direction += step;
+        }
+    }
+
+    let mut offset = direction.normalize_or_zero() * PAN_SPEED * time.delta_secs();
+
+    // dragging with the right mouse button moves the world with the cursor
+    let mouse_delta: Vec2 = motion.read().map(|event| event.delta).sum();
+    if buttons.pressed(MouseButton::Right) {
+        offset += mouse_delta * vec2(-1.0, 1.0);
+    }
+
+    transform.translation += (offset * projection.scale).extend(0.0);
+}
+
+fn place_planet(
+    mut commands: Commands,
+    mut selection: ResMut<EditorSelection>,
+    assets: Res<game::Assets>,
+    cursor: Res<WorldCursor>,
+    planets: Query<&EditorPlanet>,
+) {
+    // continue with the look of the selected planet
+    let look = selection
+        .0
+        .and_then(|entity| planets.get(entity).ok())
+        .map(|planet| planet.assets)
+        .unwrap_or_default();
+
+    let def = PlanetDef {
+        position: cursor.0.to_array(),
+        radius: PLANET_RADIUS_DEFAULT,
+        assets: look,
+        refuel: false,
+    };
+
+    selection.0 = Some(spawn_planet(&mut commands, &assets, &def));
+}
+
+fn place_goal(
+    mut commands: Commands,
+    mut selection: ResMut<EditorSelection>,
+    assets: Res<game::Assets>,
+    cursor: Res<WorldCursor>,
+    goals: Query<(Entity, &EditorGoal)>,
+) {
+    let mut radius = GOAL_RADIUS_DEFAULT;
+
+    // there is only one goal, replace the existing one
+    for (entity, goal) in &goals {
+        radius = goal.radius;
+        commands.entity(entity).despawn();
+    }
+
+    let def = GoalDef {
+        position: cursor.0.to_array(),
+        radius,
+    };
+
+    selection.0 = Some(spawn_goal(&mut commands, &assets, &def));
+}
+
+fn place_pickup(
+    mut commands: Commands,
+    mut selection: ResMut<EditorSelection>,
+    assets: Res<game::Assets>,
+    cursor: Res<WorldCursor>,
+) {
+    let def = PickupDef {
+        position: cursor.0.to_array(),
+        fuel_secs: PICKUP_FUEL_SECS,
+    };
+
+    selection.0 = Some(spawn_pickup(&mut commands, &assets, &def));
+}
+
+fn delete_selected(
+    mut commands: Commands,
+    mut selection: ResMut<EditorSelection>,
+    deletable: Query<(), Without<EditorStart>>,
+) {
+    let Some(entity) = selection.0 else {
+        return;
+    };
+
+    // the level always needs a start position
+    if deletable.contains(entity) {
+        commands.entity(entity).despawn();
+        selection.0 = None;
+    }
+}
+
+fn clear_selection(mut selection: ResMut<EditorSelection>) {
+    selection.0 = None;
+}
+
+fn edit_selected_planet(
+    mut commands: Commands,
+    mut selection: ResMut<EditorSelection>,
+    keys: Res<ButtonInput<KeyCode>>,
+    assets: Res<game::Assets>,
+    mut planets: Query<(&Transform, &mut EditorPlanet)>,
+) {
+    let Some(entity) = selection.0 else {
+        return;
+    };
+
+    let Ok((transform, mut planet)) = planets.get_mut(entity) else {
+        return;
+    };
+
+    if keys.just_pressed(KeyCode::KeyR) {
+        planet.refuel = !planet.refuel;
+    }
+
+    let count = assets.planets.len();
+
+    let look = if keys.just_pressed(KeyCode::BracketRight) {
+        (planet.assets + 1) % count
+    } else if keys.just_pressed(KeyCode::BracketLeft) {
+        (planet.assets + count - 1) % count
+    } else {
+        return;
+    };
+
+    // a different look needs a new planet
+    let def = PlanetDef {
+        position: transform.translation.xy().to_array(),
+        radius: planet.radius,
+        assets: look,
+        refuel: planet.refuel,
+    };
+
+    commands.entity(entity).despawn();
+    selection.0 = Some(spawn_planet(&mut commands, &assets, &def));
+}
+
+fn toggle_fuel_mass(mut rules: ResMut<EditorRules>) {
+    rules.0.fuel_has_mass = !rules.0.fuel_has_mass;
+}
+
+fn toggle_attitude_control(mut rules: ResMut<EditorRules>) {
+    rules.0.attitude = match rules.0.attitude {
+        AttitudeControl::Free => AttitudeControl::REALISTIC,
+        AttitudeControl::Realistic { .. } => AttitudeControl::Free,
+    };
+}
+
+fn toggle_rocket(mut rules: ResMut<EditorRules>) {
+    rules.0.rocket = match rules.0.rocket {
+        RocketKind::TwoStage => RocketKind::SingleStage,
+        RocketKind::SingleStage => RocketKind::TwoStage,
+    };
+}
+
+fn save_level(keys: Res<ButtonInput<KeyCode>>, level: EditedLevel) {
+    if !(keys.any_pressed(MODIFIER_KEYS) && keys.just_pressed(KeyCode::KeyS)) {
+        return;
+    }
+
+    match level.collect().save(CUSTOM_LEVEL_PATH) {
+        Ok(()) => info!("Saved level to {CUSTOM_LEVEL_PATH}"),
+        Err(err) => error!("Failed to save level to {CUSTOM_LEVEL_PATH}: {err}"),
+    }
+}
+
+fn load_level(
+    mut commands: Commands,
+    mut selection: ResMut<EditorSelection>,
+    keys: Res<ButtonInput<KeyCode>>,
+    assets: Res<game::Assets>,
+    editables: Query<Entity, With<Editable>>,
+) {
+    if !(keys.any_pressed(MODIFIER_KEYS) && keys.just_pressed(KeyCode::KeyL)) {
+        return;
+    }
+
+    let file = match LevelFile::load(CUSTOM_LEVEL_PATH) {
+        Ok(file) => file,
+        Err(err) => {
+            error!("Failed to load level from {CUSTOM_LEVEL_PATH}: {err}");
+            return;
+        }
+    };
+
+    for entity in &editables {
+        commands.entity(entity).despawn();
+    }
+
+    selection.0 = None;
+    spawn_level_entities(&mut commands, &assets, &file);
+
+    info!("Loaded level from {CUSTOM_LEVEL_PATH}");
+}
+
+fn start_playtest(
+    level: EditedLevel,
+    mut custom: ResMut<CustomLevel>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    custom.0 = level.collect();
+    next_screen.set(Screen::Gameplay);
+}
+
+fn draw_editor_gizmos(
+    mut gizmos: Gizmos,
+    selection: Res<EditorSelection>,
+    planets: Query<(&Transform, &EditorPlanet)>,
+    goals: Query<(&Transform, &EditorGoal)>,
+    pickups: Query<&Transform, With<EditorPickup>>,
+    start: Query<&Transform, With<EditorStart>>,
+) {
+    for (transform, planet) in &planets {
+        if planet.refuel {
+            let position = transform.translation.xy();
+            gizmos.circle_2d(position, planet.radius + 16.0, COLOR_EDITOR_REFUEL);
+        }
+    }
+
+    let Some(entity) = selection.0 else {
+        return;
+    };
+
+    let radius = planets
+        .get(entity)
+        .map(|(_, planet)| planet.radius)
+        .or_else(|_| goals.get(entity).map(|(_, goal)| goal.radius))
+        .or_else(|_| pickups.get(entity).map(|_| PICKUP_RADIUS))
+        .or_else(|_| start.get(entity).map(|_| START_RADIUS));
+
+    let transform = planets
+        .get(entity)
+        .map(|(transform, _)| transform)
+        .or_else(|_| goals.get(entity).map(|(transform, _)| transform))
+        .or_else(|_| pickups.get(entity))
+        .or_else(|_| start.get(entity));
+
+    if let (Ok(transform), Ok(radius)) = (transform, radius) {
+        let position = transform.translation.xy();
+        gizmos.circle_2d(position, radius + 8.0, COLOR_EDITOR_SELECTION);
+    }
+}
+
+fn update_status_text(
+    selection: Res<EditorSelection>,
+    rules: Res<EditorRules>,
+    planets: Query<&EditorPlanet>,
+    mut text: Single<&mut Text, With<StatusText>>,
+) {
+    let selected = match selection.0.map(|entity| planets.get(entity)) {
+        Some(Ok(planet)) => format!(
+            "Planet: look {}, radius {:.0}, refuel {}",
+            planet.assets + 1,
+            planet.radius,
+            if planet.refuel { "on" } else { "off" },
+        ),
+        Some(Err(_)) => String::new(),
+        None => "Nothing selected".to_owned(),
+    };
+
+    let turning = match rules.0.attitude {
+        AttitudeControl::Free => "free",
+        AttitudeControl::Realistic { .. } => "realistic",
+    };
+
+    let rocket = match rules.0.rocket {
+        RocketKind::TwoStage => "two stages",
+        RocketKind::SingleStage => "single stage",
+    };
+
+    let status = format!(
+        "Fuel has mass: {}, turning: {turning}, rocket: {rocket}\n{selected}",
+        if rules.0.fuel_has_mass { "on" } else { "off" },
+    );
+
+    if text.0 != status {
+        text.0 = status;
+    }
+}
@@ -0,0 +1,139 @@
+//! Feedback for high force events: camera shake and particle bursts.
+
+use crate::AppSystems;
+use crate::common::pause::PausableSystems;
+use crate::game::camera::CameraTrauma;
+use crate::game::cv::{COLOR_DEBRIS, COLOR_DUST};
+use crate::game::input::OnThurst;
+use crate::game::landing::Landed;
+use crate::game::particles::{ParticleSettings, burst_bundle};
+use crate::game::planet::Planet;
+use crate::game::player::Player;
+use crate::screens::Screen;
+use avian2d::prelude::{Collisions, ComputedMass, LinearVelocity};
+use bevy::prelude::*;
+use std::f32::consts::PI;
+use std::time::Duration;
+
+/// Impacts below this speed are not worth any feedback.
+const MIN_IMPACT_SPEED: f32 = 32.0;
+
+/// Impact speed that results in full trauma.
+const MAX_IMPACT_SPEED: f32 = 400.0;
+
+/// Passing a planet closer than this counts as a near miss.
+const NEAR_MISS_ALTITUDE: f32 = 96.0;
+
+/// Passing a planet slower than this does not count as a near miss.
+const NEAR_MISS_SPEED: f32 = 250.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(shake_on_thrust);
+
+    app.add_systems(
+        Update,
+        (impact_effects, shake_on_near_miss)
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+}
+
+fn shake_on_thrust(_: Trigger<OnThurst>, mut trauma: ResMut<CameraTrauma>) {
+    trauma.add(0.25);
+}
+
+fn impact_effects(
+    mut commands: Commands,
+    mut trauma: ResMut<CameraTrauma>,
+    collisions: Collisions,
+    player: Single<(Entity, &Transform, &ComputedMass), With<Player>>,
+    planets: Query<(), With<Planet>>,
+) {
+    let (entity, transform, mass) = player.into_inner();
+
+    for contact in collisions.collisions_with(entity) {
+        // the impulse the collision needed to stop the rocket tells us how hard it hit
+        let impact_speed = contact.total_normal_impulse_magnitude() / mass.value();
+        if impact_speed < MIN_IMPACT_SPEED {
+            continue;
+        }
+
+        let (other, normal) = if contact.collider1 == entity {
+            (contact.collider2, contact.total_normal_impulse())
+        } else {
+            (contact.collider1, -contact.total_normal_impulse())
+        };
+
+        // direction from the rocket towards the thing it hit
+        let towards = normal.normalize_or(Vec2::NEG_Y);
+        let position = transform.translation.xy() + towards * 32.0;
+
+        let strength = (impact_speed / MAX_IMPACT_SPEED).min(1.0);
+        trauma.add(0.2 + 0.6 * strength);
+
+        let count = 4 + (16.0 * strength) as usize;
+
+        if planets.contains(other) {
+            // dust kicked up from the surface
+            commands.spawn(burst_bundle(
+                position,
+                ParticleSettings {
+                    direction: -towards,
+                    spread: PI / 3.0,
+                    speed: 32.0..96.0 + impact_speed * 0.5,
+                    lifetime: Duration::from_secs_f32(1.5),
+                    size: 16.0..40.0,
+                    color_start: COLOR_DUST,
+                    color_end: COLOR_DUST,
+                    inherit_velocity: 0.0,
+                },
+                2 * count,
+            ));
+        }
+
+        // pieces of the rocket flying off
+        commands.spawn(burst_bundle(
+            position,
+            ParticleSettings {
+                direction: -towards,
+                spread: PI / 2.0,
+                speed: 64.0..128.0 + impact_speed,
+                lifetime: Duration::from_secs_f32(0.75),
+                size: 6.0..12.0,
+                color_start: COLOR_DEBRIS,
+                color_end: COLOR_DEBRIS,
+                inherit_velocity: 0.0,
+            },
+            count,
+        ));
+    }
+}
+
+fn shake_on_near_miss(
+    mut trauma: ResMut<CameraTrauma>,
+    mut armed: Local<bool>,
+    player: Single<(&Transform, &LinearVelocity, Has<Landed>), With<Player>>,
+    planets: Query<(&Transform, &Planet)>,
+) {
+    let (transform, velocity, landed) = player.into_inner();
+    let position = transform.translation.xy();
+
+    let altitude = planets
+        .iter()
+        .map(|(transform, planet)| transform.translation.xy().distance(position) - planet.radius)
+        .min_by(f32::total_cmp)
+        .unwrap_or(f32::INFINITY);
+
+    if altitude > 2.0 * NEAR_MISS_ALTITUDE {
+        // far enough away, the next close pass counts again
+        *armed = true;
+        return;
+    }
+
+    if *armed && !landed && altitude < NEAR_MISS_ALTITUDE && velocity.length() > NEAR_MISS_SPEED {
+        info!("Near miss at {:.0} units/s", velocity.length());
+        trauma.add(0.3);
+        *armed = false;
+    }
+}
@@ -0,0 +1,224 @@
+use crate::AppSystems;
+use crate::common::pause::PausableSystems;
+use crate::common::squishy::Squishy;
+use crate::game;
+use crate::game::cv::{COLOR_GOAL, COLOR_PICKUP, LAYER_GOALS};
+use crate::game::particles::{ParticleSettings, burst_bundle};
+use crate::game::player::Player;
+use crate::game::rocket::{Fuel, FuelTank};
+use crate::game::shadow::Shadow;
+use crate::screens::Screen;
+use crate::ui::widget;
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use std::f32::consts::PI;
+use std::time::Duration;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (collect_pickups, reach_goal)
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+
+    app.add_observer(show_goal_reached);
+}
+
+/// The place the player needs to reach to finish a level.
+#[derive(Component, Reflect)]
+pub struct Goal {
+    pub radius: f32,
+}
+
+/// Something the player can fly through to collect it.
+#[derive(Component, Reflect)]
+pub struct Pickup {
+    pub radius: f32,
+    pub fuel: Fuel,
+}
+
+/// Triggered on the player once it reaches the goal.
+#[derive(Event, Debug)]
+pub struct OnGoalReached;
+
+/// Marks a player that already reached the goal.
+#[derive(Component)]
+pub struct GoalReached;
+
+pub fn goal_bundle(assets: &game::Assets, radius: f32) -> impl Bundle {
+    (
+        Name::new("Goal"),
+        StateScoped(Screen::Gameplay),
+        Goal { radius },
+        LAYER_GOALS,
+        Visibility::Inherited,
+        children![(
+            Shadow::default(),
+            Squishy {
+                offset: Duration::ZERO,
+                frequency: 0.5,
+                scale_min: Vec2::splat(0.9),
+                scale_max: Vec2::splat(1.1),
+            },
+            Sprite {
+                image: assets.star_large.clone(),
+                color: COLOR_GOAL,
+                custom_size: Some(Vec2::splat(2.0 * radius)),
+                anchor: Anchor::Center,
+                ..default()
+            },
+        )],
+    )
+}
+
+pub fn pickup_bundle(assets: &game::Assets, fuel: Fuel) -> impl Bundle {
+    let radius = 32.0;
+
+    (
+        Name::new("Pickup"),
+        StateScoped(Screen::Gameplay),
+        Pickup { radius, fuel },
+        LAYER_GOALS,
+        Visibility::Inherited,
+        children![(
+            Shadow::default(),
+            Squishy {
+                offset: Duration::ZERO,
+                frequency: 1.0,
+                scale_min: Vec2::splat(0.8),
+                scale_max: Vec2::splat(1.0),
+            },
+            Sprite {
+                image: assets.star_small.clone(),
+                color: COLOR_PICKUP,
+                custom_size: Some(Vec2::splat(2.0 * radius)),
+                anchor: Anchor::Center,
+                ..default()
+            },
+        )],
+    )
+}
+
+fn collect_pickups(
+    mut commands: Commands,
+    player: Single<(&Transform, &mut FuelTank), With<Player>>,
+    pickups: Query<(Entity, &Transform, &Pickup), Without<Player>>,
+) {
+    let (player_transform, mut tank) = player.into_inner();
+
+    for (entity, transform, pickup) in pickups {
+        let distance = transform
+            .translation
+            .xy()
+            .distance(player_transform.translation.xy());
+
+        if distance > pickup.radius {
+            continue;
+        }
+
+        info!("Collected pickup with {}s of fuel", pickup.fuel.as_secs());
+        tank.refuel(pickup.fuel.as_duration());
+        commands.entity(entity).despawn();
+
+        commands.spawn(burst_bundle(
+            transform.translation.xy(),
+            ParticleSettings {
+                direction: Vec2::Y,
+                spread: PI,
+                speed: 64.0..192.0,
+                lifetime: Duration::from_secs_f32(0.8),
+                size: 8.0..16.0,
+                color_start: Color::WHITE,
+                color_end: COLOR_PICKUP,
+                inherit_velocity: 0.0,
+            },
+            24,
+        ));
+    }
+}
+
+fn reach_goal(
+    mut commands: Commands,
+    player: Single<(Entity, &Transform), (With<Player>, Without<GoalReached>)>,
+    goals: Query<(&Transform, &Goal), Without<Player>>,
+) {
+    let (player, player_transform) = player.into_inner();
+
+    let reached = goals.iter().any(|(transform, goal)| {
+        transform
+            .translation
+            .xy()
+            .distance(player_transform.translation.xy())
+            <= goal.radius
+    });
+
+    if reached {
+        info!("Player reached the goal");
+        commands
+            .entity(player)
+            .insert(GoalReached)
+            .trigger(OnGoalReached);
+    }
+}
+
+fn show_goal_reached(_: Trigger<OnGoalReached>, mut commands: Commands) {
+    commands.spawn((
+        widget::ui_root("Goal Reached"),
+        StateScoped(Screen::Gameplay),
+        children![widget::header("Goal reached!")],
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{TestApp, assert_near};
+
+    #[test]
+    fn pickup_refuels_rocket() {
+        let mut app = TestApp::new();
+        let rocket = app.spawn_rocket(Vec2::ZERO);
+        let capacity = app.fuel(rocket);
+
+        app.thrust(rocket, Vec2::Y, 4.0);
+        app.advance_secs(4.0);
+        assert_near(app.fuel(rocket), capacity - 4.0, 1.0 / 64.0);
+
+        let position = app.position(rocket);
+        let assets = game::Assets::placeholder(0);
+        let pickup = app
+            .world_mut()
+            .spawn((
+                Transform::from_translation(position.extend(0.0)),
+                pickup_bundle(&assets, Fuel::from_secs(3)),
+            ))
+            .id();
+
+        app.step();
+
+        assert!(!app.exists(pickup));
+        assert_near(app.fuel(rocket), capacity - 1.0, 1.0 / 64.0);
+    }
+
+    #[test]
+    fn reaching_goal_marks_player() {
+        let mut app = TestApp::new();
+        let rocket = app.spawn_rocket(Vec2::ZERO);
+
+        let assets = game::Assets::placeholder(0);
+        app.world_mut().spawn((
+            Transform::from_translation(vec3(0.0, 200.0, 0.0)),
+            goal_bundle(&assets, 64.0),
+        ));
+
+        app.step();
+        assert!(app.world().get::<GoalReached>(rocket).is_none());
+
+        app.thrust(rocket, Vec2::Y, 1.0);
+        app.advance_secs(10.0);
+
+        assert!(app.world().get::<GoalReached>(rocket).is_some());
+    }
+}
@@ -0,0 +1,282 @@
+//! Asks the autopilot for the next burn and shows it like an aimed input.
+//!
+//! The player requests a hint using the button in the corner or the H key.
+//! The autopilot plans in the background, starting from where the rocket was
+//! when the hint was requested. The suggested burn stays visible until the
+//! next burn starts.
+
+use crate::AppSystems;
+use crate::game;
+use crate::game::attraction::Attractor;
+use crate::game::autopilot::{Plan, PlannedBurn, Problem};
+use crate::game::config::GameConfig;
+use crate::game::goal::Goal;
+use crate::game::input::OnThurst;
+use crate::game::level::LevelRules;
+use crate::game::planet::Planet;
+use crate::game::player::Player;
+use crate::game::rocket::{FuelTank, RcsTank, Stages};
+use crate::game::{cv, trajectory};
+use crate::menus::Menu;
+use crate::screens::Screen;
+use crate::ui::widget;
+use avian2d::prelude::{ComputedMass, LinearVelocity};
+use bevy::input::common_conditions::input_just_pressed;
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use bevy::tasks::futures::check_ready;
+use bevy::tasks::{AsyncComputeTaskPool, Task};
+use bevy::ui::Val::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(show_hint);
+    app.add_observer(clear_hint_on_thrust);
+
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        (spawn_hint_button, spawn_hint_line),
+    );
+
+    app.add_systems(OnExit(Screen::Gameplay), cancel_hint);
+
+    app.add_systems(
+        Update,
+        request_hint_on_key
+            .run_if(
+                in_state(Screen::Gameplay)
+                    .and(in_state(Menu::None))
+                    .and(input_just_pressed(KeyCode::KeyH)),
+            )
+            .in_set(AppSystems::RecordInput),
+    );
+
+    app.add_systems(
+        Update,
+        show_planned_hint
+            .run_if(in_state(Screen::Gameplay).and(resource_exists::<HintPlanning>))
+            .in_set(AppSystems::Update),
+    );
+
+    app.add_systems(
+        Update,
+        follow_player
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::UpdateMarkers),
+    );
+}
+
+/// Requests the autopilot to suggest the next burn of the player.
+#[derive(Event, Debug)]
+pub struct RequestHint;
+
+/// The autopilot searching for the hint that was requested last.
+#[derive(Resource)]
+struct HintPlanning(Task<Option<Plan>>);
+
+/// The line showing the direction and length of the suggested burn.
+#[derive(Component)]
+struct HintLine;
+
+#[derive(Component)]
+struct HintLabel;
+
+fn spawn_hint_button(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Hint"),
+        StateScoped(Screen::Gameplay),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Px(16.0),
+            bottom: Px(16.0),
+            align_items: AlignItems::Center,
+            column_gap: Px(8.0),
+            ..default()
+        },
+        children![
+            widget::button_small("?", request_hint_on_click),
+            (HintLabel, widget::label(""), Pickable::IGNORE),
+        ],
+    ));
+}
+
+fn spawn_hint_line(mut commands: Commands, assets: Res<game::Assets>) {
+    commands.spawn((
+        Name::new("HintLine"),
+        StateScoped(Screen::Gameplay),
+        HintLine,
+        Visibility::Hidden,
+        Sprite {
+            image: assets.line.clone(),
+            anchor: Anchor::CenterLeft,
+            color: cv::COLOR_HINT,
+            custom_size: Some(vec2(128.0, 8.0)),
+            image_mode: SpriteImageMode::Sliced(TextureSlicer {
+                border: BorderRect::axes(32.0, 0.0),
+                ..default()
+            }),
+            ..default()
+        },
+    ));
+}
+
+fn request_hint_on_click(_: Trigger<Pointer<Click>>, mut commands: Commands) {
+    commands.trigger(RequestHint);
+}
+
+fn request_hint_on_key(mut commands: Commands) {
+    commands.trigger(RequestHint);
+}
+
+fn show_hint(
+    _: Trigger<RequestHint>,
+    mut commands: Commands,
+    rules: Res<LevelRules>,
+    player: Query<
+        (
+            &Transform,
+            &LinearVelocity,
+            &ComputedMass,
+            &Stages,
+            &FuelTank,
+            Option<&RcsTank>,
+        ),
+        With<Player>,
+    >,
+    attractors: Query<(&Transform, &ComputedMass, &Planet), (With<Attractor>, Without<HintLine>)>,
+    goals: Query<(&Transform, &Goal), Without<HintLine>>,
+    mut label: Single<&mut Text, With<HintLabel>>,
+) {
+    let Ok((transform, velocity, mass, stages, fuel, rcs)) = player.single() else {
+        return;
+    };
+
+    let Ok((goal_transform, goal)) = goals.single() else {
+        label.0 = "No goal to fly to".into();
+        return;
+    };
+
+    let problem = Problem {
+        position: transform.translation.xy(),
+        velocity: velocity.0,
+        rotation: transform.rotation,
+        mass: mass.value(),
+        thrust: stages.active().thrust,
+        fuel: fuel.remaining.as_secs(),
+        fuel_mass: stages.active().fuel_mass,
+        rcs_fuel: rcs.map_or(0.0, |rcs| rcs.0.remaining.as_secs()),
+        rules: rules.clone(),
+        bodies: trajectory::collect_bodies(attractors),
+        goal: goal_transform.translation.xy(),
+        goal_radius: goal.radius,
+    };
+
+    // the search takes a while, keep the game running in the meantime.
+    // replacing an older request drops and cancels its task.
+    let task = AsyncComputeTaskPool::get().spawn(async move { problem.solve() });
+    commands.insert_resource(HintPlanning(task));
+
+    label.0 = "Planning...".into();
+}
+
+fn show_planned_hint(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    mut planning: ResMut<HintPlanning>,
+    mut line: Single<(&mut Sprite, &mut Transform, &mut Visibility), With<HintLine>>,
+    mut label: Single<&mut Text, With<HintLabel>>,
+) {
+    let Some(plan) = check_ready(&mut planning.0) else {
+        return;
+    };
+
+    commands.remove_resource::<HintPlanning>();
+
+    let (sprite, line_transform, visibility) = &mut *line;
+
+    // only the first burn of the plan starts right away
+    let Some(burn) = plan.and_then(|plan| plan.burns.first().copied()) else {
+        label.0 = "No route found".into();
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    };
+
+    let PlannedBurn {
+        direction,
+        duration,
+        ..
+    } = burn;
+
+    label.0 = format!("Burn {duration:1.2}s");
+
+    line_transform.rotation = Quat::from_rotation_z(direction.to_angle());
+    sprite.custom_size = Some(vec2(duration * config.aim_length_per_second, 8.0));
+    visibility.set_if_neq(Visibility::Visible);
+}
+
+fn cancel_hint(mut commands: Commands) {
+    commands.remove_resource::<HintPlanning>();
+}
+
+fn clear_hint_on_thrust(
+    _: Trigger<OnThurst>,
+    mut commands: Commands,
+    mut line: Single<&mut Visibility, With<HintLine>>,
+    mut label: Single<&mut Text, With<HintLabel>>,
+) {
+    // a hint that is still being planned would start from an outdated state
+    commands.remove_resource::<HintPlanning>();
+
+    line.set_if_neq(Visibility::Hidden);
+    label.0.clear();
+}
+
+fn follow_player(
+    player: Query<&Transform, With<Player>>,
+    mut line: Single<&mut Transform, (With<HintLine>, Without<Player>)>,
+) {
+    let Ok(player) = player.single() else {
+        return;
+    };
+
+    line.translation = player.translation.xy().extend(cv::LAYER_PLAYER_INPUT.0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::goal::goal_bundle;
+    use crate::testing::TestApp;
+
+    #[test]
+    fn hint_shows_burn_until_thrust() {
+        let mut app = TestApp::new().with_plugins(plugin);
+        app.world_mut()
+            .insert_resource(game::Assets::placeholder(0));
+
+        let rocket = app.spawn_rocket(Vec2::ZERO);
+        app.world_mut().spawn((
+            Transform::from_translation(vec3(0.0, 800.0, 0.0)),
+            goal_bundle(&game::Assets::placeholder(0), 64.0),
+        ));
+
+        app.advance_secs(0.5);
+        app.world_mut().trigger(RequestHint);
+
+        // wait for the autopilot to finish planning
+        app.step();
+        while app.world().contains_resource::<HintPlanning>() {
+            app.step();
+        }
+
+        let mut lines = app
+            .world_mut()
+            .query_filtered::<&Visibility, With<HintLine>>();
+        assert_eq!(lines.single(app.world()).unwrap(), Visibility::Visible);
+
+        let mut labels = app.world_mut().query_filtered::<&Text, With<HintLabel>>();
+        assert!(labels.single(app.world()).unwrap().0.starts_with("Burn"));
+
+        app.thrust(rocket, Vec2::Y, 1.0);
+        assert_eq!(lines.single(app.world()).unwrap(), Visibility::Hidden);
+    }
+}
@@ -0,0 +1,175 @@
+//! The heads-up display shown during gameplay.
+
+use crate::AppSystems;
+use crate::common::pause::PausableSystems;
+use crate::game::attraction::Attractor;
+use crate::game::planet::Planet;
+use crate::game::player::Player;
+use crate::game::rocket::FuelTank;
+use crate::screens::Screen;
+use crate::ui::widget;
+use avian2d::prelude::LinearVelocity;
+use bevy::prelude::*;
+use bevy::ui::Val::*;
+use std::time::Duration;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ElapsedTime>();
+
+    app.add_systems(OnEnter(Screen::Gameplay), (reset_elapsed_time, spawn_hud));
+
+    app.add_systems(
+        Update,
+        tick_elapsed_time
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::TickTimers)
+            .in_set(PausableSystems),
+    );
+
+    app.add_systems(
+        Update,
+        (
+            update_fuel_gauge,
+            update_speed_label,
+            update_altitude_label,
+            update_time_label,
+        )
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::Update),
+    );
+}
+
+/// Time spent in the current level, not counting the time the game was paused.
+#[derive(Resource, Default)]
+pub struct ElapsedTime(pub Duration);
+
+#[derive(Component)]
+struct FuelGaugeFill;
+
+#[derive(Component)]
+struct SpeedLabel;
+
+#[derive(Component)]
+struct AltitudeLabel;
+
+#[derive(Component)]
+struct TimeLabel;
+
+fn spawn_hud(mut commands: Commands) {
+    commands.spawn((
+        Name::new("HUD"),
+        StateScoped(Screen::Gameplay),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Px(16.0),
+            top: Px(16.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Px(8.0),
+            ..default()
+        },
+        Pickable::IGNORE,
+        children![
+            (
+                Name::new("Fuel Gauge"),
+                Node {
+                    width: Px(200.0),
+                    height: Px(16.0),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+                BorderRadius::all(Px(4.0)),
+                children![(
+                    Name::new("Fuel Gauge Fill"),
+                    FuelGaugeFill,
+                    Node {
+                        width: Percent(100.0),
+                        height: Percent(100.0),
+                        ..default()
+                    },
+                    BackgroundColor(widget::LABEL_TEXT),
+                    BorderRadius::all(Px(4.0)),
+                )],
+            ),
+            (widget::label("0 u/s"), SpeedLabel),
+            (widget::label("0 u"), AltitudeLabel),
+            (widget::label("0:00.0"), TimeLabel),
+        ],
+    ));
+}
+
+fn reset_elapsed_time(mut elapsed: ResMut<ElapsedTime>) {
+    elapsed.0 = Duration::ZERO;
+}
+
+fn tick_elapsed_time(mut elapsed: ResMut<ElapsedTime>, time: Res<Time>) {
+    elapsed.0 += time.delta();
+}
+
+fn update_fuel_gauge(
+    player: Single<&FuelTank, With<Player>>,
+    mut fill: Single<&mut Node, With<FuelGaugeFill>>,
+) {
+    let capacity = player.capacity.as_secs();
+
+    let fraction = if capacity > 0.0 {
+        player.remaining.as_secs() / capacity
+    } else {
+        0.0
+    };
+
+    fill.width = Percent(100.0 * fraction.clamp(0.0, 1.0));
+}
+
+fn update_speed_label(
+    player: Single<&LinearVelocity, With<Player>>,
+    mut label: Single<&mut Text, With<SpeedLabel>>,
+) {
+    label.0 = format!("{:.0} u/s", player.length());
+}
+
+fn update_altitude_label(
+    player: Single<&Transform, With<Player>>,
+    attractors: Query<(&Transform, &Planet), With<Attractor>>,
+    mut label: Single<&mut Text, With<AltitudeLabel>>,
+) {
+    let position = player.translation.xy();
+
+    // distance to the surface of the nearest planet
+    let altitude = attractors
+        .iter()
+        .map(|(transform, planet)| transform.translation.xy().distance(position) - planet.radius)
+        .min_by(f32::total_cmp);
+
+    label.0 = match altitude {
+        Some(altitude) => format!("{:.0} u", altitude.max(0.0)),
+        None => "-".to_owned(),
+    };
+}
+
+fn update_time_label(elapsed: Res<ElapsedTime>, mut label: Single<&mut Text, With<TimeLabel>>) {
+    label.0 = format_time(elapsed.0.as_secs_f32());
+}
+
+/// Formats seconds as minutes, seconds and tenths, e.g. `1:02.5`.
+fn format_time(secs: f32) -> String {
+    // round once, so 59.96 seconds carry over into the minutes
+    let tenths = (secs * 10.0).round() as u64;
+    format!(
+        "{}:{:02}.{}",
+        tenths / 600,
+        (tenths % 600) / 10,
+        tenths % 10
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_rounds_into_the_next_minute() {
+        assert_eq!(format_time(0.0), "0:00.0");
+        assert_eq!(format_time(62.54), "1:02.5");
+        assert_eq!(format_time(59.96), "1:00.0");
+    }
+}
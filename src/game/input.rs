@@ -1,6 +1,8 @@
 use crate::common::pause::{PausableSystems, Pause};
+use crate::game::config::GameConfig;
 use crate::{AppSystems, MainCamera};
 use bevy::input::ButtonState;
+use bevy::input::common_conditions::input_just_pressed;
 use bevy::input::mouse::MouseButtonInput;
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
@@ -8,6 +10,7 @@ use std::time::Duration;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_event::<OnThurst>();
+    app.add_event::<OnJettison>();
 
     app.add_systems(
         Update,
@@ -17,6 +20,14 @@ pub(super) fn plugin(app: &mut App) {
             .in_set(PausableSystems),
     );
 
+    app.add_systems(
+        Update,
+        jettison_on_key
+            .run_if(input_just_pressed(KeyCode::Space))
+            .in_set(AppSystems::RecordInput)
+            .in_set(PausableSystems),
+    );
+
     app.add_observer(input_deactivated);
 
     app.add_systems(OnEnter(Pause(true)), cancel_all_inputs_on_pause);
@@ -33,7 +44,11 @@ pub struct InputActive {
 }
 
 impl InputActive {
-    pub fn state(&self, context: &InputTransformContext) -> Option<InputState> {
+    pub fn state(
+        &self,
+        context: &InputTransformContext,
+        config: &GameConfig,
+    ) -> Option<InputState> {
         let (camera, camera_transform) = context;
 
         let start = camera
@@ -50,7 +65,7 @@ impl InputActive {
             return None;
         }
 
-        let duration = Duration::from_secs_f32(length / 100.0);
+        let duration = Duration::from_secs_f32(length / config.aim_length_per_second);
         Some(InputState {
             duration,
             start,
@@ -73,6 +88,10 @@ pub struct OnThurst {
     pub duration: Duration,
 }
 
+/// Requests to drop the currently burning stage of a rocket.
+#[derive(Event, Debug)]
+pub struct OnJettison;
+
 fn mouse_input_start(
     mut commands: Commands,
     mut events: EventReader<MouseButtonInput>,
@@ -189,12 +208,13 @@ fn input_deactivated(
     mut commands: Commands,
     inputs: Query<&InputActive>,
     camera: Single<InputTransformContext, With<MainCamera>>,
+    config: Res<GameConfig>,
 ) {
     let Ok(input) = inputs.get(trigger.target()) else {
         return;
     };
 
-    let Some(state) = input.state(&camera) else {
+    let Some(state) = input.state(&camera, &config) else {
         return;
     };
 
@@ -209,6 +229,13 @@ fn input_deactivated(
     commands.entity(trigger.target()).trigger(thrust);
 }
 
+fn jettison_on_key(mut commands: Commands, targets: Query<Entity, With<Input>>) {
+    for entity in &targets {
+        info!("Trigger jettison event");
+        commands.entity(entity).trigger(OnJettison);
+    }
+}
+
 fn cancel_all_inputs_on_pause(mut commands: Commands, inputs: Query<(Entity, &mut InputActive)>) {
     info!("Cancel all active inputs, if any");
     for (entity, mut input) in inputs {
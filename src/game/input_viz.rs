@@ -1,7 +1,9 @@
+use crate::game::config::GameConfig;
 use crate::game::cv;
 use crate::game::input::{InputActive, InputTransformContext};
+use crate::game::level::LevelRules;
 use crate::game::player::Player;
-use crate::game::rocket::FuelTank;
+use crate::game::rocket::{FuelTank, RcsTank};
 use crate::screens::Screen;
 use crate::{AppSystems, MainCamera, game};
 use bevy::prelude::*;
@@ -52,8 +54,10 @@ fn spawn_indicator_line(mut commands: Commands, assets: Res<game::Assets>) {
 }
 
 fn visualize_thrust_input(
-    player: Query<(&Transform, &InputActive, &FuelTank), With<Player>>,
+    player: Query<(&Transform, &InputActive, &FuelTank, Option<&RcsTank>), With<Player>>,
     input_transform: Single<InputTransformContext, With<MainCamera>>,
+    config: Res<GameConfig>,
+    rules: Res<LevelRules>,
 
     burn_time_label: Single<
         (&mut Text2d, &mut TextColor, &mut Visibility, &mut Transform),
@@ -75,13 +79,13 @@ fn visualize_thrust_input(
     let (mut line_sprite, mut line_transform, mut line_visibility) = burn_line.into_inner();
 
     // the input state from the player
-    let Ok((player, input, fuel)) = player.single() else {
+    let Ok((player, input, fuel, rcs)) = player.single() else {
         line_visibility.set_if_neq(Visibility::Hidden);
         burn_time_visibility.set_if_neq(Visibility::Hidden);
         return;
     };
 
-    let Some(state) = input.state(&input_transform) else {
+    let Some(state) = input.state(&input_transform, &config) else {
         line_visibility.set_if_neq(Visibility::Hidden);
         burn_time_visibility.set_if_neq(Visibility::Hidden);
         return;
@@ -129,7 +133,17 @@ fn visualize_thrust_input(
         Color::WHITE
     };
 
-    burn_time_text.0 = format!("{:1.2}s", burn_time);
+    // time the rocket needs to turn into the direction of thrust
+    let turn_time = rules
+        .attitude
+        .turn_duration(player.rotation, thrust_vec, rcs)
+        .as_secs_f32();
+
+    burn_time_text.0 = if turn_time > 0.0 {
+        format!("{:1.2}s\nturn {:1.2}s", burn_time, turn_time)
+    } else {
+        format!("{:1.2}s", burn_time)
+    };
     burn_time_color.set_if_neq(TextColor(color));
     burn_time_visibility.set_if_neq(Visibility::Visible);
     burn_time_transform.translation = origin + (thrust_vec + spacing).extend(0.0);
@@ -0,0 +1,295 @@
+use crate::AppSystems;
+use crate::common::pause::PausableSystems;
+use crate::game::planet::Planet;
+use crate::game::player::Thrust;
+use crate::game::rocket::{FuelTank, Rocket};
+use crate::screens::Screen;
+use avian2d::prelude::{
+    AngularVelocity, Collider, Collisions, LinearVelocity, RigidBody, SimpleCollider,
+};
+use bevy::prelude::*;
+use std::f32::consts::PI;
+
+/// The highest speed along the surface normal that still counts as a soft touchdown.
+const MAX_TOUCHDOWN_SPEED: f32 = 64.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_required_components::<Rocket, VelocityBeforeStep>();
+
+    app.add_systems(
+        Update,
+        (take_off, hold_landed_rockets, refuel_landed_rockets)
+            .chain()
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::PrePhysics)
+            .in_set(PausableSystems),
+    );
+
+    // physics steps right after the fixed update
+    app.add_systems(
+        FixedUpdate,
+        remember_velocity.run_if(in_state(Screen::Gameplay)),
+    );
+
+    app.add_systems(
+        Update,
+        detect_touchdown
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+}
+
+/// A rocket that is resting on the surface of a planet.
+#[derive(Component, Reflect)]
+pub struct Landed {
+    pub planet: Entity,
+    /// Surface normal at the landing site, pointing away from the planet.
+    pub normal: Vec2,
+    /// Distance from the center of the rocket to its bottom.
+    pub clearance: f32,
+}
+
+/// A planet that refuels rockets landed on it.
+#[derive(Component, Reflect)]
+pub struct RefuelStation {
+    /// Seconds of fuel added per second.
+    pub rate: f32,
+}
+
+impl Default for RefuelStation {
+    fn default() -> Self {
+        Self { rate: 4.0 }
+    }
+}
+
+/// The velocity of a rocket going into the last physics step.
+#[derive(Component, Default)]
+struct VelocityBeforeStep(Vec2);
+
+fn remember_velocity(rockets: Query<(&mut VelocityBeforeStep, &LinearVelocity)>) {
+    for (mut before, velocity) in rockets {
+        before.0 = velocity.0;
+    }
+}
+
+fn detect_touchdown(
+    mut commands: Commands,
+    collisions: Collisions,
+    rockets: Query<
+        (
+            Entity,
+            &Transform,
+            &Collider,
+            &VelocityBeforeStep,
+            &mut RigidBody,
+            &mut LinearVelocity,
+            &mut AngularVelocity,
+        ),
+        (With<Rocket>, Without<Landed>, Without<Thrust>),
+    >,
+    planets: Query<&Transform, With<Planet>>,
+) {
+    for (entity, transform, collider, before, mut body, mut velocity, mut angular) in rockets {
+        for contact in collisions.collisions_with(entity) {
+            let other = if contact.collider1 == entity {
+                contact.collider2
+            } else {
+                contact.collider1
+            };
+
+            let Ok(planet_transform) = planets.get(other) else {
+                continue;
+            };
+
+            let normal = (transform.translation.xy() - planet_transform.translation.xy())
+                .normalize_or(Vec2::Y);
+
+            // the contact impulse only covers the last substep, so it can miss most of
+            // a hard hit. use the speed towards the planet before the step instead
+            let impact_speed = -before.0.dot(normal);
+            if impact_speed > MAX_TOUCHDOWN_SPEED {
+                continue;
+            }
+
+            // measure the rocket while it is standing upright
+            let clearance = -collider.aabb(Vec2::ZERO, 0.0).min.y;
+
+            info!("Rocket touched down with {:1.2} units/s", impact_speed);
+
+            *body = RigidBody::Kinematic;
+            velocity.0 = Vec2::ZERO;
+            angular.0 = 0.0;
+
+            commands.entity(entity).insert(Landed {
+                planet: other,
+                normal,
+                clearance,
+            });
+
+            break;
+        }
+    }
+}
+
+fn hold_landed_rockets(
+    mut commands: Commands,
+    rockets: Query<(Entity, &mut Transform, &Landed)>,
+    planets: Query<(&Transform, &Planet), Without<Landed>>,
+    time: Res<Time>,
+) {
+    for (entity, mut transform, landed) in rockets {
+        let Ok((planet_transform, planet)) = planets.get(landed.planet) else {
+            // the planet is gone, nothing left to stand on
+            commands.entity(entity).remove::<Landed>();
+            continue;
+        };
+
+        let position =
+            planet_transform.translation.xy() + landed.normal * (planet.radius + landed.clearance);
+
+        // the rocket sprites point upwards, align them to the surface normal
+        let rotation = Quat::from_rotation_z(landed.normal.to_angle() - PI / 2.0);
+
+        let mut current = transform.translation.xy();
+        current.smooth_nudge(&position, 20.0, time.delta_secs());
+
+        transform.translation.x = current.x;
+        transform.translation.y = current.y;
+
+        transform
+            .rotation
+            .smooth_nudge(&rotation, 10.0, time.delta_secs());
+    }
+}
+
+fn refuel_landed_rockets(
+    rockets: Query<(&mut FuelTank, &Landed)>,
+    stations: Query<&RefuelStation>,
+    time: Res<Time>,
+) {
+    for (mut tank, landed) in rockets {
+        let Ok(station) = stations.get(landed.planet) else {
+            continue;
+        };
+
+        tank.refuel(time.delta().mul_f32(station.rate));
+    }
+}
+
+fn take_off(
+    mut commands: Commands,
+    rockets: Query<(Entity, &mut RigidBody), (With<Landed>, Added<Thrust>)>,
+) {
+    for (entity, mut body) in rockets {
+        info!("Rocket takes off");
+
+        *body = RigidBody::Dynamic;
+        commands.entity(entity).remove::<Landed>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::rocket::Fuel;
+    use crate::testing::{TestApp, assert_near};
+    use std::time::Duration;
+
+    /// Distance from the center of the rocket to its bottom.
+    const CLEARANCE: f32 = 56.0;
+
+    /// A rocket resting on a refuel station, with the given seconds of fuel left.
+    fn landed_rocket(app: &mut TestApp, fuel: f32) -> (Entity, Entity) {
+        let planet = app.spawn_attractor(Vec2::ZERO, 128.0);
+        app.world_mut()
+            .entity_mut(planet)
+            .insert(RefuelStation { rate: 2.0 });
+
+        let rocket = app.spawn_rocket(vec2(0.0, 128.0 + CLEARANCE));
+
+        let mut entity = app.world_mut().entity_mut(rocket);
+        entity.get_mut::<FuelTank>().unwrap().remaining = Fuel::new(Duration::from_secs_f32(fuel));
+        entity.insert((
+            RigidBody::Kinematic,
+            Landed {
+                planet,
+                normal: Vec2::Y,
+                clearance: CLEARANCE,
+            },
+        ));
+
+        (rocket, planet)
+    }
+
+    #[test]
+    fn slow_contact_lands() {
+        let mut app = TestApp::new();
+        let planet = app.spawn_attractor(Vec2::ZERO, 128.0);
+        let rocket = app.spawn_rocket(vec2(0.0, 128.0 + CLEARANCE + 4.0));
+
+        app.advance_secs(1.0);
+
+        let landed = app.world().get::<Landed>(rocket).expect("rocket landed");
+        assert_eq!(landed.planet, planet);
+        assert_near(landed.normal.y, 1.0, 1e-3);
+        assert_eq!(
+            app.world().get::<RigidBody>(rocket),
+            Some(&RigidBody::Kinematic)
+        );
+    }
+
+    #[test]
+    fn fast_contact_does_not_land() {
+        let mut app = TestApp::new();
+        app.spawn_attractor(Vec2::ZERO, 128.0);
+        let rocket = app.spawn_rocket(vec2(0.0, 512.0));
+
+        let speed = 4.0 * MAX_TOUCHDOWN_SPEED;
+        app.world_mut()
+            .entity_mut(rocket)
+            .insert(LinearVelocity(Vec2::NEG_Y * speed));
+
+        // step until the rocket hits the planet
+        for _ in 0..128 {
+            app.step();
+
+            if app.velocity(rocket).y > -speed / 2.0 {
+                break;
+            }
+        }
+
+        assert!(app.velocity(rocket).y > -speed / 2.0, "rocket never hit");
+        assert!(app.world().get::<Landed>(rocket).is_none());
+        assert_eq!(
+            app.world().get::<RigidBody>(rocket),
+            Some(&RigidBody::Dynamic)
+        );
+    }
+
+    #[test]
+    fn refuel_station_fills_the_tank() {
+        let mut app = TestApp::new();
+        let (rocket, _) = landed_rocket(&mut app, 1.0);
+
+        app.advance_secs(1.0);
+
+        assert_near(app.fuel(rocket), 3.0, 0.1);
+    }
+
+    #[test]
+    fn thrust_takes_off() {
+        let mut app = TestApp::new();
+        let (rocket, _) = landed_rocket(&mut app, 5.0);
+        app.step();
+
+        app.thrust(rocket, Vec2::Y, 1.0);
+        app.step();
+
+        assert!(app.world().get::<Landed>(rocket).is_none());
+        assert_eq!(
+            app.world().get::<RigidBody>(rocket),
+            Some(&RigidBody::Dynamic)
+        );
+    }
+}
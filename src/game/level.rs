@@ -1,81 +1,138 @@
-use crate::common::rand::{Generate, Rand};
 use crate::game;
-use crate::game::cv::LAYER_STARS;
-use crate::game::planet::CropCache;
-use crate::game::shadow::Shadow;
-use crate::game::wiggle::Wiggle;
-use crate::game::{planet, player};
+use crate::game::config::GameConfig;
+use crate::game::landing::RefuelStation;
+use crate::game::level_file::LevelFile;
+use crate::game::rocket::{AttitudeControl, Fuel, RocketDef, RocketKind};
+use crate::game::tutorial::{Tutorial, TutorialAssets, TutorialScript};
+use crate::game::{goal, planet, player};
 use crate::screens::Screen;
 use bevy::prelude::*;
-use bevy::sprite::Anchor;
-use rand::Rng;
-use rand::prelude::IndexedRandom;
-use std::f32::consts::PI;
+use serde::{Deserialize, Serialize};
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(OnEnter(Screen::Gameplay), (spawn_level, spawn_stars));
+    app.init_resource::<Level>();
+    app.init_resource::<LevelRules>();
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_level);
+}
+
+/// The level that is spawned when entering the gameplay screen.
+#[derive(Resource, Reflect, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub enum Level {
+    #[default]
+    Sandbox,
+    Tutorial,
+    /// The level in [`CustomLevel`], e.g. from the editor.
+    Custom,
+}
+
+/// A level that is not built into the game.
+#[derive(Resource, Clone, Default, Debug)]
+pub struct CustomLevel(pub LevelFile);
+
+/// Rules that can be changed from level to level.
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone, Default, Debug)]
+#[serde(default)]
+pub struct LevelRules {
+    /// Fuel adds to the mass of the rocket, which gets lighter while burning.
+    pub fuel_has_mass: bool,
+
+    /// How the rocket turns towards the direction of thrust.
+    pub attitude: AttitudeControl,
+
+    /// The rocket the player flies.
+    pub rocket: RocketKind,
 }
 
 pub fn spawn_level(
     mut commands: Commands,
+    level: Res<Level>,
+    custom: Option<Res<CustomLevel>>,
     assets: Res<game::Assets>,
-    mut crop_cache: ResMut<CropCache>,
-    mut images: ResMut<Assets<Image>>,
+    config: Res<GameConfig>,
+    tutorials: Res<TutorialAssets>,
+    scripts: Res<Assets<TutorialScript>>,
 ) {
+    match *level {
+        Level::Sandbox | Level::Custom => {
+            commands.remove_resource::<Tutorial>();
+        }
+
+        Level::Tutorial => {
+            let steps = match scripts.get(&tutorials.basics) {
+                Some(script) => script.steps.clone(),
+                None => {
+                    warn!("Tutorial is not loaded");
+                    Vec::new()
+                }
+            };
+
+            commands.insert_resource(Tutorial::new(steps));
+        }
+    }
+
+    let file = level_file(*level, custom.as_deref());
+    spawn_level_file(&mut commands, &assets, &config, &file);
+}
+
+/// The description of the given level.
+pub fn level_file(level: Level, custom: Option<&CustomLevel>) -> LevelFile {
+    match level {
+        Level::Sandbox => LevelFile::sandbox(),
+        Level::Tutorial => LevelFile::tutorial(),
+        Level::Custom => custom.map(|custom| custom.0.clone()).unwrap_or_default(),
+    }
+}
+
+/// Spawns the player, planets, goal and pickups of a level and applies its rules and light.
+pub fn spawn_level_file(
+    commands: &mut Commands,
+    assets: &game::Assets,
+    config: &GameConfig,
+    file: &LevelFile,
+) {
+    commands.insert_resource(file.rules.clone());
+    commands.insert_resource(file.light);
+
+    let rocket = RocketDef::new(file.rules.rocket, assets, config);
+
     commands
         .spawn((
             Name::new("Player"),
             StateScoped(Screen::Gameplay),
-            player::bundle(&assets),
+            Transform::from_translation(Vec2::from(file.start).extend(0.0)),
+            player::bundle(&rocket),
         ))
         .observe(player::slow_time_on_input)
         .observe(player::reset_time_after_input)
         .observe(player::handle_on_thrust);
 
-    // spawn a planet
-    commands.spawn((
-        Transform::from_xyz(-50., 350., 0.),
-        planet::bundle(&assets.planets[0], &mut crop_cache, &mut images, 128.0),
-    ));
-
-    // spawn a planet
-    commands.spawn((
-        Transform::from_xyz(100., 650., 0.),
-        planet::bundle(&assets.planets[1], &mut crop_cache, &mut images, 128.0),
-    ));
-    // spawn a planet
-    commands.spawn((
-        Transform::from_xyz(60., -250., 0.),
-        planet::bundle(&assets.planets[2], &mut crop_cache, &mut images, 96.0),
-    ));
-}
+    for def in &file.planets {
+        let Some(planet_assets) = assets.planets.get(def.assets) else {
+            warn!("Level uses unknown planet assets {}", def.assets);
+            continue;
+        };
 
-fn spawn_stars(mut commands: Commands, mut rand: ResMut<Rand>, assets: Res<game::Assets>) {
-    let mut g = Generate::new(4096.0, 0.0, vec2(0.0, 1024.0));
-    let points = g.generate(|radius| rand.vec2() * radius, 500, 256.0);
+        let mut planet = commands.spawn((
+            Transform::from_translation(Vec2::from(def.position).extend(0.0)),
+            planet::bundle(planet_assets, def.radius),
+        ));
 
-    for point in points {
-        let rotation = rand.random_range(0. ..2.0 * PI);
+        if def.refuel {
+            planet.insert(RefuelStation::default());
+        }
+    }
 
+    if let Some(goal) = &file.goal {
         commands.spawn((
-            Name::new("Star"),
-            StateScoped(Screen::Gameplay),
-            LAYER_STARS,
-            Shadow::default(),
-            Wiggle {
-                offset: point.floor(),
-                offset_angle: rotation,
-                ..default()
-            },
-            Sprite {
-                image: Handle::clone(
-                    [&assets.star_small, &assets.star_large]
-                        .choose(&mut rand)
-                        .unwrap(),
-                ),
-                anchor: Anchor::Center,
-                ..default()
-            },
+            Transform::from_translation(Vec2::from(goal.position).extend(0.0)),
+            goal::goal_bundle(assets, goal.radius),
+        ));
+    }
+
+    for pickup in &file.pickups {
+        commands.spawn((
+            Transform::from_translation(Vec2::from(pickup.position).extend(0.0)),
+            goal::pickup_bundle(assets, Fuel::from_secs(pickup.fuel_secs)),
         ));
     }
 }
@@ -0,0 +1,167 @@
+//! A data description of a level, stored as RON.
+//!
+//! The built-in levels are described using this format too, so that the
+//! editor can open them as a starting point.
+
+use crate::game::level::LevelRules;
+use crate::game::light::LightSource;
+use bevy::math::vec2;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Where the editor saves its level.
+pub const CUSTOM_LEVEL_PATH: &str = "assets/levels/custom.level.ron";
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct LevelFile {
+    /// Start position of the rocket.
+    pub start: [f32; 2],
+    pub planets: Vec<PlanetDef>,
+    #[serde(default)]
+    pub goal: Option<GoalDef>,
+    #[serde(default)]
+    pub pickups: Vec<PickupDef>,
+    #[serde(default)]
+    pub rules: LevelRules,
+    /// The light shadows fall away from, e.g. a sun in the level.
+    #[serde(default)]
+    pub light: LightSource,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PlanetDef {
+    pub position: [f32; 2],
+    pub radius: f32,
+    /// Index into the planet assets, see `game::Assets::planets`.
+    pub assets: usize,
+    /// Planets with a refuel station refill the tank of a landed rocket.
+    #[serde(default)]
+    pub refuel: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GoalDef {
+    pub position: [f32; 2],
+    pub radius: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PickupDef {
+    pub position: [f32; 2],
+    /// Fuel in seconds of burn time.
+    pub fuel_secs: u64,
+}
+
+impl LevelFile {
+    /// A small system with three planets, a goal and two pickups.
+    pub fn sandbox() -> Self {
+        Self {
+            start: [0.0, 0.0],
+            planets: vec![
+                PlanetDef {
+                    position: [-50.0, 350.0],
+                    radius: 128.0,
+                    assets: 0,
+                    refuel: false,
+                },
+                PlanetDef {
+                    position: [100.0, 650.0],
+                    radius: 128.0,
+                    assets: 1,
+                    refuel: false,
+                },
+                PlanetDef {
+                    position: [60.0, -250.0],
+                    radius: 96.0,
+                    assets: 2,
+                    refuel: true,
+                },
+            ],
+            goal: Some(GoalDef {
+                position: [0.0, 1200.0],
+                radius: 64.0,
+            }),
+            pickups: vec![
+                PickupDef {
+                    position: [280.0, 320.0],
+                    fuel_secs: 5,
+                },
+                PickupDef {
+                    position: [-260.0, 700.0],
+                    fuel_secs: 5,
+                },
+            ],
+            rules: LevelRules::default(),
+            // a sun shining down from the top left of the system
+            light: LightSource::Point {
+                position: vec2(-2400., 2400.),
+                height: 1200.,
+            },
+        }
+    }
+
+    /// A single planet to land on.
+    pub fn tutorial() -> Self {
+        Self {
+            start: [0.0, 0.0],
+            planets: vec![PlanetDef {
+                position: [0.0, -400.0],
+                radius: 128.0,
+                assets: 0,
+                refuel: true,
+            }],
+            goal: None,
+            pickups: Vec::new(),
+            rules: LevelRules::default(),
+            light: LightSource::default(),
+        }
+    }
+
+    pub fn from_ron(source: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(source)
+    }
+
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        let source = std::fs::read_to_string(path)?;
+        Ok(Self::from_ron(&source)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        std::fs::write(path, self.to_ron()?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn light_is_read_from_the_level() {
+        let level = LevelFile::from_ron(
+            "(start: (0, 0), planets: [], light: Point(position: (100.0, 200.0), height: 50.0))",
+        )
+        .unwrap();
+
+        let LightSource::Point { position, height } = level.light else {
+            panic!("expected a point light, got {:?}", level.light);
+        };
+
+        assert_eq!(position, vec2(100.0, 200.0));
+        assert_eq!(height, 50.0);
+
+        // levels without a light get the default one
+        let level = LevelFile::from_ron("(start: (0, 0), planets: [])").unwrap();
+        assert!(matches!(level.light, LightSource::Directional { .. }));
+    }
+}
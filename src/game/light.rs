@@ -0,0 +1,171 @@
+//! The light that illuminates the level.
+//!
+//! Shadows are offset and stretched away from the [`LightSource`] and planets
+//! get a terminator, darkening the side facing away from the light.
+
+use crate::AppSystems;
+use crate::game::cv::{COLOR_TERMINATOR, LAYER_PLANET_TERMINATOR};
+use crate::game::planet::Planet;
+use bevy::asset::RenderAssetUsages;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use serde::{Deserialize, Serialize};
+
+/// Size of the generated terminator texture in pixels.
+const TERMINATOR_IMAGE_SIZE: u32 = 256;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<LightSource>();
+
+    app.add_systems(Startup, create_terminator_image);
+    app.add_observer(add_terminator_to_planet);
+
+    app.add_systems(Update, rotate_terminators.in_set(AppSystems::UpdateMarkers));
+}
+
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone, Copy, Debug)]
+pub enum LightSource {
+    /// A light that is infinitely far away, e.g. a distant sun.
+    /// The length of the direction is the shadow offset per unit of height.
+    Directional { direction: Vec2 },
+
+    /// A light at a point in the level, hovering at the given height above it.
+    Point { position: Vec2, height: f32 },
+}
+
+impl Default for LightSource {
+    fn default() -> Self {
+        LightSource::Directional {
+            direction: vec2(0.5, -0.7),
+        }
+    }
+}
+
+impl LightSource {
+    /// The direction the light travels in at the given position.
+    pub fn direction_at(&self, position: Vec2) -> Vec2 {
+        match *self {
+            LightSource::Directional { direction } => direction.normalize_or_zero(),
+            LightSource::Point {
+                position: light, ..
+            } => (position - light).normalize_or_zero(),
+        }
+    }
+
+    /// Projects something at the given position and height onto the ground.
+    /// Returns the offset and the scale of its shadow.
+    pub fn project(&self, position: Vec2, height: f32) -> (Vec2, f32) {
+        match *self {
+            LightSource::Directional { direction } => (direction * height, 1.0),
+
+            LightSource::Point {
+                position: light,
+                height: light_height,
+            } => {
+                // do not let the shadow explode when getting close to the light
+                let height = height.min(light_height * 0.9);
+                let scale = light_height / (light_height - height);
+                ((position - light) * (scale - 1.0), scale)
+            }
+        }
+    }
+}
+
+/// A soft half disc that darkens the side of the planet facing away from the light.
+#[derive(Resource)]
+struct TerminatorImage(Handle<Image>);
+
+#[derive(Component)]
+struct Terminator;
+
+fn create_terminator_image(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let size = Extent3d {
+        width: TERMINATOR_IMAGE_SIZE,
+        height: TERMINATOR_IMAGE_SIZE,
+        depth_or_array_layers: 1,
+    };
+
+    let mut image = Image::new_fill(
+        size,
+        TextureDimension::D2,
+        &[255, 255, 255, 0],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+
+    let center = Vec2::splat(TERMINATOR_IMAGE_SIZE as f32 / 2.0);
+
+    for y in 0..TERMINATOR_IMAGE_SIZE {
+        for x in 0..TERMINATOR_IMAGE_SIZE {
+            // position within the unit circle
+            let position = (vec2(x as f32 + 0.5, y as f32 + 0.5) - center) / center.x;
+
+            // anti aliased edge of the disc
+            let edge = ((1.0 - position.length()) * center.x).clamp(0.0, 1.0);
+
+            // the night side is towards positive x, with a soft transition
+            let night = smoothstep(-0.2, 0.5, position.x);
+
+            if let Some(pixel) = image.pixel_bytes_mut(UVec3::new(x, y, 0)) {
+                pixel[3] = (edge * night * 255.0) as u8;
+            }
+        }
+    }
+
+    commands.insert_resource(TerminatorImage(images.add(image)));
+}
+
+fn add_terminator_to_planet(
+    trigger: Trigger<OnAdd, Planet>,
+    mut commands: Commands,
+    image: Res<TerminatorImage>,
+    planets: Query<&Planet>,
+) -> Result {
+    let planet = planets.get(trigger.target())?;
+
+    commands.entity(trigger.target()).with_child((
+        Name::new("Terminator"),
+        Terminator,
+        LAYER_PLANET_TERMINATOR,
+        Sprite {
+            image: image.0.clone(),
+            color: COLOR_TERMINATOR,
+            custom_size: Some(Vec2::splat(2.0 * planet.radius)),
+            ..default()
+        },
+    ));
+
+    Ok(())
+}
+
+/// Turns the night side of each planet away from the light.
+fn rotate_terminators(
+    light: Res<LightSource>,
+    planets: Query<&GlobalTransform, With<Planet>>,
+    mut terminators: Query<(&ChildOf, &mut Transform), With<Terminator>>,
+) {
+    for (child_of, mut transform) in &mut terminators {
+        let Ok(planet) = planets.get(child_of.parent()) else {
+            continue;
+        };
+
+        let (_, planet_rotation, planet_position) = planet.to_scale_rotation_translation();
+
+        let direction = light.direction_at(planet_position.xy());
+        if direction == Vec2::ZERO {
+            continue;
+        }
+
+        let rotation = planet_rotation.inverse() * Quat::from_rotation_z(direction.to_angle());
+
+        // do not trigger change detection if nothing has changed
+        if transform.rotation != rotation {
+            transform.rotation = rotation;
+        }
+    }
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
@@ -0,0 +1,464 @@
+//! A minimap in the corner of the screen and a full screen system map.
+//!
+//! Both are rendered by the same orthographic camera. While flying, the camera
+//! renders into an image shown in the corner of the screen. Opening the system
+//! map pauses the game and points the camera at the window instead.
+
+use crate::common::pause::Pause;
+use crate::game::cv::{
+    COLOR_GOAL, COLOR_MAP_BACKGROUND, COLOR_MAP_PLAYER, COLOR_PICKUP, COLOR_PREDICTED_PATH,
+    LAYER_MAP_ICONS,
+};
+use crate::game::goal::{Goal, Pickup};
+use crate::game::player::Player;
+use crate::game::trajectory::PredictedPath;
+use crate::menus::Menu;
+use crate::screens::Screen;
+use crate::ui::widget;
+use crate::{AppSystems, game};
+use bevy::asset::RenderAssetUsages;
+use bevy::input::common_conditions::input_just_pressed;
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use bevy::render::camera::{RenderTarget, ScalingMode};
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages};
+use bevy::render::view::RenderLayers;
+use bevy::sprite::Anchor;
+use bevy::ui::Val::*;
+use bevy::window::WindowRef;
+
+/// Size of the minimap render target in pixels.
+const MINIMAP_RESOLUTION: u32 = 256;
+
+/// Size of the minimap on screen.
+const MINIMAP_SIZE: f32 = 128.0;
+
+/// Width of the world visible on the map at the default zoom level.
+const MAP_VIEW_WIDTH: f32 = 8192.0;
+
+const MAP_ZOOM_MIN: f32 = 0.125;
+const MAP_ZOOM_MAX: f32 = 4.0;
+
+/// Pan speed using the keyboard, in screen widths per second.
+const MAP_PAN_SPEED: f32 = 0.75;
+
+/// Render layer of things that should only be visible on the map.
+const MAP_RENDER_LAYER: usize = 1;
+
+/// Number of dots used to draw the predicted path on the map.
+const MAP_PATH_DOTS: usize = 32;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Startup, create_minimap_image);
+
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        (spawn_map_camera, spawn_minimap, spawn_map_path_dots),
+    );
+
+    app.add_systems(OnEnter(Menu::SystemMap), open_system_map);
+    app.add_systems(OnExit(Menu::SystemMap), close_system_map);
+
+    app.add_systems(
+        Update,
+        (
+            toggle_system_map.run_if(
+                in_state(Screen::Gameplay)
+                    .and(in_state(Menu::None).or(in_state(Menu::SystemMap)))
+                    .and(input_just_pressed(KeyCode::KeyM)),
+            ),
+            leave_system_map
+                .run_if(in_state(Menu::SystemMap).and(input_just_pressed(KeyCode::Escape))),
+            (pan_system_map, zoom_system_map).run_if(in_state(Menu::SystemMap)),
+        )
+            .in_set(AppSystems::RecordInput),
+    );
+
+    app.add_systems(
+        Update,
+        spawn_map_icons
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::Update),
+    );
+
+    app.add_systems(
+        Update,
+        follow_player
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::UpdateCamera),
+    );
+
+    app.add_systems(
+        Update,
+        (update_map_icons, update_map_path_dots)
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::UpdateMarkers),
+    );
+}
+
+/// The image the minimap is rendered into.
+#[derive(Resource)]
+struct MinimapImage(Handle<Image>);
+
+#[derive(Component)]
+struct MapCamera {
+    /// Offset of the camera relative to the player.
+    pan: Vec2,
+}
+
+#[derive(Component)]
+struct Minimap;
+
+/// A sprite on the map that follows an entity in the world.
+#[derive(Component)]
+struct MapIcon {
+    target: Entity,
+    /// Size of the icon relative to the width of the visible map.
+    size: f32,
+}
+
+#[derive(Component)]
+struct MapPathDot(usize);
+
+fn create_minimap_image(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let size = Extent3d {
+        width: MINIMAP_RESOLUTION,
+        height: MINIMAP_RESOLUTION,
+        depth_or_array_layers: 1,
+    };
+
+    let mut image = Image::new_fill(
+        size,
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Bgra8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+
+    image.texture_descriptor.usage =
+        TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT;
+
+    commands.insert_resource(MinimapImage(images.add(image)));
+}
+
+fn spawn_map_camera(mut commands: Commands, image: Res<MinimapImage>) {
+    let mut projection = OrthographicProjection::default_2d();
+
+    projection.scaling_mode = ScalingMode::FixedHorizontal {
+        viewport_width: MAP_VIEW_WIDTH,
+    };
+
+    commands.spawn((
+        Name::new("MapCamera"),
+        StateScoped(Screen::Gameplay),
+        MapCamera { pan: Vec2::ZERO },
+        Camera2d,
+        Camera {
+            order: -1,
+            target: RenderTarget::Image(image.0.clone().into()),
+            clear_color: ClearColorConfig::Custom(COLOR_MAP_BACKGROUND),
+            ..default()
+        },
+        Projection::Orthographic(projection),
+        RenderLayers::from_layers(&[0, MAP_RENDER_LAYER]),
+    ));
+}
+
+fn spawn_minimap(mut commands: Commands, image: Res<MinimapImage>) {
+    commands
+        .spawn((
+            Name::new("Minimap"),
+            StateScoped(Screen::Gameplay),
+            Minimap,
+            Node {
+                position_type: PositionType::Absolute,
+                right: Px(16.0),
+                bottom: Px(16.0),
+                width: Px(MINIMAP_SIZE),
+                height: Px(MINIMAP_SIZE),
+                border: UiRect::all(Px(2.0)),
+                ..default()
+            },
+            BorderColor(widget::LABEL_TEXT),
+            BorderRadius::all(Px(4.0)),
+            ImageNode::new(image.0.clone()),
+        ))
+        .observe(open_system_map_on_click);
+}
+
+fn spawn_map_path_dots(mut commands: Commands, assets: Res<game::Assets>) {
+    for idx in 0..MAP_PATH_DOTS {
+        commands.spawn((
+            Name::new("MapPathDot"),
+            StateScoped(Screen::Gameplay),
+            MapPathDot(idx),
+            LAYER_MAP_ICONS,
+            RenderLayers::layer(MAP_RENDER_LAYER),
+            Visibility::Hidden,
+            Sprite {
+                image: assets.star_small.clone(),
+                color: COLOR_PREDICTED_PATH,
+                custom_size: Some(Vec2::ONE),
+                anchor: Anchor::Center,
+                ..default()
+            },
+        ));
+    }
+}
+
+fn map_icon_bundle(image: Handle<Image>, target: Entity, color: Color, size: f32) -> impl Bundle {
+    (
+        Name::new("MapIcon"),
+        StateScoped(Screen::Gameplay),
+        MapIcon { target, size },
+        LAYER_MAP_ICONS,
+        RenderLayers::layer(MAP_RENDER_LAYER),
+        Sprite {
+            image,
+            color,
+            custom_size: Some(Vec2::ONE),
+            anchor: Anchor::Center,
+            ..default()
+        },
+    )
+}
+
+fn spawn_map_icons(
+    mut commands: Commands,
+    assets: Res<game::Assets>,
+    players: Query<Entity, Added<Player>>,
+    goals: Query<Entity, Added<Goal>>,
+    pickups: Query<Entity, Added<Pickup>>,
+) {
+    for player in &players {
+        commands.spawn(map_icon_bundle(
+            assets.star_small.clone(),
+            player,
+            COLOR_MAP_PLAYER,
+            1.0 / 32.0,
+        ));
+    }
+
+    for goal in &goals {
+        commands.spawn(map_icon_bundle(
+            assets.star_large.clone(),
+            goal,
+            COLOR_GOAL,
+            1.0 / 24.0,
+        ));
+    }
+
+    for pickup in &pickups {
+        commands.spawn(map_icon_bundle(
+            assets.star_small.clone(),
+            pickup,
+            COLOR_PICKUP,
+            1.0 / 48.0,
+        ));
+    }
+}
+
+fn follow_player(
+    camera: Single<(&mut Transform, &MapCamera)>,
+    player: Single<&Transform, (With<Player>, Without<MapCamera>)>,
+) {
+    let (mut transform, camera) = camera.into_inner();
+
+    let position = player.translation.xy() + camera.pan;
+    transform.translation.x = position.x;
+    transform.translation.y = position.y;
+}
+
+/// Width of the world currently visible on the map.
+fn visible_width(projection: &Projection) -> f32 {
+    match projection {
+        Projection::Orthographic(projection) => projection.area.width(),
+        _ => MAP_VIEW_WIDTH,
+    }
+}
+
+fn update_map_icons(
+    mut commands: Commands,
+    camera: Single<&Projection, With<MapCamera>>,
+    targets: Query<&Transform, Without<MapIcon>>,
+    icons: Query<(Entity, &MapIcon, &mut Transform)>,
+) {
+    let width = visible_width(&camera);
+
+    for (entity, icon, mut transform) in icons {
+        let Ok(target) = targets.get(icon.target) else {
+            // the target is gone, so is the icon
+            commands.entity(entity).despawn();
+            continue;
+        };
+
+        transform.translation.x = target.translation.x;
+        transform.translation.y = target.translation.y;
+        transform.scale = Vec3::splat(icon.size * width);
+    }
+}
+
+fn update_map_path_dots(
+    camera: Single<&Projection, With<MapCamera>>,
+    player: Single<&PredictedPath, With<Player>>,
+    dots: Query<(&MapPathDot, &mut Transform, &mut Visibility)>,
+) {
+    let width = visible_width(&camera);
+    let stride = (player.points.len() / MAP_PATH_DOTS).max(1);
+
+    for (dot, mut transform, mut visibility) in dots {
+        let Some(point) = player.points.get((dot.0 + 1) * stride - 1) else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+
+        transform.translation.x = point.x;
+        transform.translation.y = point.y;
+        transform.scale = Vec3::splat(width / 128.0);
+        visibility.set_if_neq(Visibility::Inherited);
+    }
+}
+
+fn open_system_map_on_click(
+    _: Trigger<Pointer<Click>>,
+    menu: Res<State<Menu>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    if *menu.get() == Menu::None {
+        next_menu.set(Menu::SystemMap);
+    }
+}
+
+fn toggle_system_map(menu: Res<State<Menu>>, mut next_menu: ResMut<NextState<Menu>>) {
+    match menu.get() {
+        Menu::SystemMap => next_menu.set(Menu::None),
+        _ => next_menu.set(Menu::SystemMap),
+    }
+}
+
+fn leave_system_map(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::None);
+}
+
+fn open_system_map(
+    mut commands: Commands,
+    mut next_pause: ResMut<NextState<Pause>>,
+    cameras: Query<&mut Camera, With<MapCamera>>,
+    minimaps: Query<&mut Visibility, With<Minimap>>,
+) {
+    next_pause.set(Pause(true));
+
+    // render the map on top of the game, this also makes it the default ui camera
+    for mut camera in cameras {
+        camera.order = 1;
+        camera.target = RenderTarget::Window(WindowRef::Primary);
+    }
+
+    for mut visibility in minimaps {
+        *visibility = Visibility::Hidden;
+    }
+
+    commands.spawn((
+        Name::new("System Map Help"),
+        StateScoped(Menu::SystemMap),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Percent(100.0),
+            bottom: Px(40.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        Pickable::IGNORE,
+        children![(
+            widget::label("Drag to pan, scroll to zoom.\nPress M or Escape to close the map."),
+            TextLayout::new_with_justify(JustifyText::Center),
+        )],
+    ));
+}
+
+fn close_system_map(
+    image: Res<MinimapImage>,
+    cameras: Query<(&mut Camera, &mut MapCamera, &mut Projection)>,
+    minimaps: Query<&mut Visibility, With<Minimap>>,
+) {
+    for (mut camera, mut map, mut projection) in cameras {
+        camera.order = -1;
+        camera.target = RenderTarget::Image(image.0.clone().into());
+        map.pan = Vec2::ZERO;
+
+        if let Projection::Orthographic(projection) = projection.as_mut() {
+            projection.scale = 1.0;
+        }
+    }
+
+    for mut visibility in minimaps {
+        *visibility = Visibility::Inherited;
+    }
+}
+
+fn pan_system_map(
+    camera: Single<(&mut MapCamera, &Projection)>,
+    window: Single<&Window>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut cursor: EventReader<CursorMoved>,
+    time: Res<Time<Real>>,
+) {
+    let (mut map, projection) = camera.into_inner();
+
+    let width = visible_width(projection);
+
+    // world units per logical pixel
+    let pixel = width / window.width();
+
+    for event in cursor.read() {
+        let Some(delta) = event.delta else {
+            continue;
+        };
+
+        if mouse.pressed(MouseButton::Left) {
+            // drag the map along with the cursor, screen y points down
+            map.pan -= vec2(delta.x, -delta.y) * pixel;
+        }
+    }
+
+    let mut direction = Vec2::ZERO;
+
+    if keys.any_pressed([KeyCode::KeyA, KeyCode::ArrowLeft]) {
+        direction.x -= 1.0;
+    }
+
+    if keys.any_pressed([KeyCode::KeyD, KeyCode::ArrowRight]) {
+        direction.x += 1.0;
+    }
+
+    if keys.any_pressed([KeyCode::KeyS, KeyCode::ArrowDown]) {
+        direction.y -= 1.0;
+    }
+
+    if keys.any_pressed([KeyCode::KeyW, KeyCode::ArrowUp]) {
+        direction.y += 1.0;
+    }
+
+    map.pan += direction.normalize_or_zero() * MAP_PAN_SPEED * width * time.delta_secs();
+}
+
+fn zoom_system_map(
+    mut projection: Single<&mut Projection, With<MapCamera>>,
+    mut wheel: EventReader<MouseWheel>,
+) {
+    let Projection::Orthographic(projection) = projection.as_mut() else {
+        return;
+    };
+
+    for event in wheel.read() {
+        let steps = match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / 100.0,
+        };
+
+        // scrolling up zooms into the map
+        projection.scale =
+            (projection.scale * 0.9_f32.powf(steps)).clamp(MAP_ZOOM_MIN, MAP_ZOOM_MAX);
+    }
+}
@@ -1,18 +1,40 @@
 use avian2d::prelude::Gravity;
 use bevy::prelude::*;
 
+pub mod animation;
 pub mod assets;
+pub mod attract;
 pub mod attraction;
+pub mod autopilot;
 pub mod camera;
+pub mod config;
 pub mod cv;
+#[cfg(feature = "dev")]
+pub mod editor;
+pub mod effects;
+pub mod goal;
+pub mod hint;
+pub mod hud;
 pub mod input;
 pub mod input_viz;
+pub mod landing;
 pub mod layer;
 pub mod level;
+pub mod level_file;
+pub mod light;
+pub mod minimap;
+pub mod nav;
+pub mod particles;
 pub mod planet;
 pub mod player;
+pub mod processed;
 pub mod rocket;
 pub mod shadow;
+pub mod sprite_effects;
+pub mod starfield;
+pub mod trail;
+pub mod trajectory;
+pub mod tutorial;
 pub mod wiggle;
 
 pub use assets::Assets;
@@ -20,6 +42,7 @@ pub use assets::Assets;
 pub fn plugin(app: &mut App) {
     app.add_plugins((
         assets::plugin,
+        config::plugin,
         shadow::plugin,
         level::plugin,
         wiggle::plugin,
@@ -31,8 +54,30 @@ pub fn plugin(app: &mut App) {
         input_viz::plugin,
         planet::plugin,
         attraction::plugin,
+        attract::plugin,
     ));
 
+    app.add_plugins((
+        landing::plugin,
+        goal::plugin,
+        tutorial::plugin,
+        hud::plugin,
+        nav::plugin,
+        trajectory::plugin,
+        minimap::plugin,
+        particles::plugin,
+        effects::plugin,
+        trail::plugin,
+        starfield::plugin,
+        light::plugin,
+        sprite_effects::plugin,
+        animation::plugin,
+        hint::plugin,
+    ));
+
+    #[cfg(feature = "dev")]
+    app.add_plugins(editor::plugin);
+
     app.insert_resource(ClearColor(cv::COLOR_BACKGROUND));
 
     // configure game physics
@@ -40,3 +85,20 @@ pub fn plugin(app: &mut App) {
     // app.insert_resource(SubstepCount(6));
     // app.insert_resource(DefaultFriction(Friction::new(0.0)));
 }
+
+/// The parts of the game that move the rocket, without anything that needs
+/// rendering or assets. Used to simulate levels, see `crate::headless`.
+pub fn headless_plugin(app: &mut App) {
+    app.init_resource::<config::GameConfig>();
+    app.init_resource::<level::LevelRules>();
+
+    app.add_plugins((
+        attraction::plugin,
+        rocket::plugin,
+        planet::plugin,
+        landing::plugin,
+        goal::plugin,
+    ));
+
+    app.insert_resource(Gravity::ZERO);
+}
@@ -0,0 +1,257 @@
+//! Navigation indicators at the edge of the screen that point to
+//! the goal, pickups and the nearest planet.
+
+use crate::common::markers;
+use crate::common::markers::{Marker, MarkerTarget};
+use crate::game::cv::{COLOR_GOAL, COLOR_NAV_PLANET, COLOR_PICKUP};
+use crate::game::goal::{Goal, Pickup};
+use crate::game::planet::Planet;
+use crate::game::player::Player;
+use crate::screens::Screen;
+use crate::{AppSystems, MainCamera, game};
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+
+/// Distance between the indicators and the edge of the screen.
+const SCREEN_MARGIN: f32 = 48.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            spawn_target_indicators,
+            spawn_planet_indicator,
+            track_nearest_planet,
+        )
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::Update),
+    );
+
+    app.add_systems(
+        Update,
+        update_nav_indicators
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::UpdateMarkers)
+            .before(markers::update_markers),
+    );
+}
+
+#[derive(Component)]
+struct NavIndicator {
+    color: Color,
+    alpha: f32,
+}
+
+#[derive(Component)]
+struct NearestPlanetIndicator;
+
+#[derive(Component)]
+struct NavDistanceLabel;
+
+fn indicator_bundle(
+    assets: &game::Assets,
+    player: Entity,
+    target: MarkerTarget,
+    color: Color,
+) -> impl Bundle {
+    (
+        Name::new("NavIndicator"),
+        StateScoped(Screen::Gameplay),
+        NavIndicator { color, alpha: 0.0 },
+        Marker {
+            follow: player,
+            target,
+            offset: 0.0,
+            offset_z: 2.0,
+        },
+        Sprite {
+            image: assets.line.clone(),
+            anchor: Anchor::CenterLeft,
+            color: color.with_alpha(0.0),
+            custom_size: Some(vec2(48.0, 12.0)),
+            ..default()
+        },
+        children![(
+            NavDistanceLabel,
+            Text2d::default(),
+            TextFont::from_font_size(16.0),
+            TextColor(color.with_alpha(0.0)),
+            Transform::from_xyz(-24.0, 0.0, 0.0),
+        )],
+    )
+}
+
+fn spawn_target_indicators(
+    mut commands: Commands,
+    assets: Res<game::Assets>,
+    player: Single<Entity, With<Player>>,
+    goals: Query<Entity, Added<Goal>>,
+    pickups: Query<Entity, Added<Pickup>>,
+) {
+    for goal in &goals {
+        commands.spawn(indicator_bundle(
+            &assets,
+            *player,
+            MarkerTarget::Dynamic {
+                target: goal,
+                offset: Vec2::ZERO,
+            },
+            COLOR_GOAL,
+        ));
+    }
+
+    for pickup in &pickups {
+        commands.spawn(indicator_bundle(
+            &assets,
+            *player,
+            MarkerTarget::Dynamic {
+                target: pickup,
+                offset: Vec2::ZERO,
+            },
+            COLOR_PICKUP,
+        ));
+    }
+}
+
+fn spawn_planet_indicator(
+    mut commands: Commands,
+    assets: Res<game::Assets>,
+    player: Query<(Entity, &Transform), Added<Player>>,
+) {
+    for (player, transform) in &player {
+        commands.spawn((
+            indicator_bundle(
+                &assets,
+                player,
+                MarkerTarget::Static {
+                    position: transform.translation.xy(),
+                },
+                COLOR_NAV_PLANET,
+            ),
+            NearestPlanetIndicator,
+        ));
+    }
+}
+
+fn track_nearest_planet(
+    player: Single<&Transform, With<Player>>,
+    planets: Query<(Entity, &Transform, &Planet)>,
+    indicators: Query<&mut Marker, With<NearestPlanetIndicator>>,
+) {
+    let position = player.translation.xy();
+
+    let nearest = planets
+        .iter()
+        .map(|(entity, transform, planet)| {
+            let distance = transform.translation.xy().distance(position) - planet.radius;
+            (entity, distance)
+        })
+        .min_by(|(_, lhs), (_, rhs)| lhs.total_cmp(rhs));
+
+    let Some((nearest, _)) = nearest else {
+        return;
+    };
+
+    for mut marker in indicators {
+        marker.target = MarkerTarget::Dynamic {
+            target: nearest,
+            offset: Vec2::ZERO,
+        };
+    }
+}
+
+fn update_nav_indicators(
+    mut commands: Commands,
+    camera: Single<(&Transform, &Projection), With<MainCamera>>,
+    transforms: Query<&Transform, (Without<NavIndicator>, Without<NavDistanceLabel>)>,
+    indicators: Query<(
+        Entity,
+        &mut Marker,
+        &mut NavIndicator,
+        &mut Sprite,
+        &Transform,
+        &Children,
+    )>,
+    mut labels: Query<
+        (&mut Text2d, &mut TextColor, &mut Transform),
+        (With<NavDistanceLabel>, Without<NavIndicator>),
+    >,
+    time: Res<Time<Real>>,
+) {
+    let (camera_transform, projection) = camera.into_inner();
+
+    let Projection::Orthographic(projection) = projection else {
+        return;
+    };
+
+    // the part of the world that is currently visible
+    let screen = Rect::from_center_size(
+        camera_transform.translation.xy() + projection.area.center(),
+        projection.area.size(),
+    );
+
+    let inner = screen.inflate(-SCREEN_MARGIN);
+
+    for (entity, mut marker, mut indicator, mut sprite, transform, children) in indicators {
+        let target = marker.target.position(|target| {
+            let transform = transforms.get(target).ok()?;
+            Some(transform.translation.xy())
+        });
+
+        let Some(target) = target else {
+            // the target is gone, so is the indicator
+            commands.entity(entity).despawn();
+            continue;
+        };
+
+        let Ok(follow) = transforms.get(marker.follow) else {
+            continue;
+        };
+
+        let origin = follow.translation.xy();
+        let delta = target - origin;
+        let distance = delta.length();
+        let direction = delta.normalize_or(Vec2::X);
+
+        // move the indicator along the direction until it hits the edge of the screen
+        let exit = ray_exit_distance(inner, origin, direction).max(0.0);
+        marker.offset = exit.min(distance);
+
+        // fade out the indicator once the target is on screen
+        let alpha_target = if screen.contains(target) { 0.0 } else { 1.0 };
+        indicator
+            .alpha
+            .smooth_nudge(&alpha_target, 8.0, time.delta_secs());
+
+        sprite.color = indicator.color.with_alpha(indicator.alpha);
+
+        for child in children.iter() {
+            let Ok((mut text, mut color, mut label_transform)) = labels.get_mut(child) else {
+                continue;
+            };
+
+            text.0 = format!("{:.0}", distance);
+            color.0 = indicator.color.with_alpha(indicator.alpha);
+
+            // keep the label upright, no matter where the arrow points to
+            label_transform.rotation = transform.rotation.inverse();
+        }
+    }
+}
+
+/// Distance along `direction` from `origin` until the ray leaves `rect`.
+fn ray_exit_distance(rect: Rect, origin: Vec2, direction: Vec2) -> f32 {
+    let exit_x = match direction.x {
+        x if x > 0.0 => (rect.max.x - origin.x) / x,
+        x if x < 0.0 => (rect.min.x - origin.x) / x,
+        _ => f32::INFINITY,
+    };
+
+    let exit_y = match direction.y {
+        y if y > 0.0 => (rect.max.y - origin.y) / y,
+        y if y < 0.0 => (rect.min.y - origin.y) / y,
+        _ => f32::INFINITY,
+    };
+
+    exit_x.min(exit_y)
+}
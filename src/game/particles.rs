@@ -0,0 +1,256 @@
+//! A lightweight CPU particle system built on plain sprites.
+//!
+//! A [`ParticleEmitter`] spawns particles in world space, either continuously
+//! or as a single burst. Particles move in a straight line, fade and shrink
+//! until they are gone. Everything is simulated on the CPU, so this works on
+//! every render backend including WebGL2.
+
+use crate::AppSystems;
+use crate::common::pause::PausableSystems;
+use crate::common::rand::Rand;
+use crate::game::cv::LAYER_PARTICLES;
+use crate::screens::Screen;
+use avian2d::prelude::LinearVelocity;
+use bevy::asset::RenderAssetUsages;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use rand::Rng;
+use std::f32::consts::PI;
+use std::ops::Range;
+use std::time::Duration;
+
+/// Size of the generated particle texture in pixels.
+const PARTICLE_IMAGE_SIZE: u32 = 32;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Startup, create_particle_image);
+
+    app.add_systems(
+        Update,
+        update_emitters
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::PrePhysics)
+            .in_set(PausableSystems),
+    );
+
+    app.add_systems(
+        Update,
+        update_particles
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+}
+
+/// A soft round dot used as the texture of all particles.
+#[derive(Resource)]
+struct ParticleImage(Handle<Image>);
+
+/// Describes the particles spawned by an emitter.
+#[derive(Clone)]
+pub struct ParticleSettings {
+    /// Main direction the particles move in, relative to the emitter.
+    pub direction: Vec2,
+    /// Maximum angle between a particle and the main direction, in radians.
+    pub spread: f32,
+    pub speed: Range<f32>,
+    pub lifetime: Duration,
+    pub size: Range<f32>,
+    pub color_start: Color,
+    pub color_end: Color,
+    /// How much of the velocity of the emitting body is passed on to the particles.
+    pub inherit_velocity: f32,
+}
+
+#[derive(Clone)]
+pub enum EmitterMode {
+    /// Spawns particles at the given rate per second while the intensity is above zero.
+    Continuous { rate: f32 },
+    /// Spawns the given number of particles once, then removes the emitter entity.
+    Burst { count: usize },
+}
+
+#[derive(Component, Clone)]
+#[require(Transform)]
+pub struct ParticleEmitter {
+    pub settings: ParticleSettings,
+    pub mode: EmitterMode,
+    /// Scales the number, speed and size of the particles, between zero and one.
+    pub intensity: f32,
+    /// Fractional particles carried over to the next frame.
+    pending: f32,
+}
+
+impl ParticleEmitter {
+    pub fn continuous(settings: ParticleSettings, rate: f32) -> Self {
+        Self {
+            settings,
+            mode: EmitterMode::Continuous { rate },
+            intensity: 0.0,
+            pending: 0.0,
+        }
+    }
+
+    pub fn burst(settings: ParticleSettings, count: usize) -> Self {
+        Self {
+            settings,
+            mode: EmitterMode::Burst { count },
+            intensity: 1.0,
+            pending: 0.0,
+        }
+    }
+}
+
+/// Spawns an entity that emits a single burst of particles at the given position.
+pub fn burst_bundle(position: Vec2, settings: ParticleSettings, count: usize) -> impl Bundle {
+    (
+        Name::new("ParticleBurst"),
+        StateScoped(Screen::Gameplay),
+        Transform::from_translation(position.extend(0.0)),
+        ParticleEmitter::burst(settings, count),
+    )
+}
+
+/// A single particle.
+#[derive(Component)]
+pub struct Particle {
+    pub velocity: Vec2,
+    pub age: Duration,
+    pub lifetime: Duration,
+    pub color_start: Color,
+    pub color_end: Color,
+}
+
+fn create_particle_image(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let size = Extent3d {
+        width: PARTICLE_IMAGE_SIZE,
+        height: PARTICLE_IMAGE_SIZE,
+        depth_or_array_layers: 1,
+    };
+
+    let mut image = Image::new_fill(
+        size,
+        TextureDimension::D2,
+        &[255, 255, 255, 0],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+
+    let center = Vec2::splat(PARTICLE_IMAGE_SIZE as f32 / 2.0);
+
+    for y in 0..PARTICLE_IMAGE_SIZE {
+        for x in 0..PARTICLE_IMAGE_SIZE {
+            let position = vec2(x as f32 + 0.5, y as f32 + 0.5);
+            let distance = position.distance(center) / center.x;
+
+            // solid in the center, fading out towards the edge
+            let alpha = (1.0 - distance).clamp(0.0, 1.0).powf(0.75);
+
+            if let Some(pixel) = image.pixel_bytes_mut(UVec3::new(x, y, 0)) {
+                pixel[3] = (alpha * 255.0) as u8;
+            }
+        }
+    }
+
+    commands.insert_resource(ParticleImage(images.add(image)));
+}
+
+fn update_emitters(
+    mut commands: Commands,
+    mut rand: ResMut<Rand>,
+    time: Res<Time>,
+    image: Res<ParticleImage>,
+    emitters: Query<(Entity, &mut ParticleEmitter, &GlobalTransform)>,
+    parents: Query<&ChildOf>,
+    velocities: Query<&LinearVelocity>,
+) {
+    for (entity, mut emitter, transform) in emitters {
+        let count = match emitter.mode {
+            EmitterMode::Continuous { rate } => {
+                emitter.pending += rate * emitter.intensity * time.delta_secs();
+                let count = emitter.pending.floor();
+                emitter.pending -= count;
+                count as usize
+            }
+
+            EmitterMode::Burst { count } => {
+                commands.entity(entity).despawn();
+                count
+            }
+        };
+
+        if count == 0 {
+            continue;
+        }
+
+        // velocity of the body this emitter is attached to, if any
+        let body_velocity = std::iter::once(entity)
+            .chain(parents.iter_ancestors(entity))
+            .find_map(|entity| velocities.get(entity).ok())
+            .map(|velocity| velocity.0)
+            .unwrap_or_default();
+
+        let (_, rotation, translation) = transform.to_scale_rotation_translation();
+
+        let settings = &emitter.settings;
+        let direction = (rotation * settings.direction.extend(0.0)).xy();
+        let scale = 0.5 + 0.5 * emitter.intensity;
+
+        for _ in 0..count {
+            let angle = rand.random_range(-settings.spread..=settings.spread);
+            let speed = rand.random_range(settings.speed.clone()) * scale;
+            let size = rand.random_range(settings.size.clone()) * scale;
+
+            // vary the lifetime a little, so the particles do not vanish all at once
+            let lifetime = settings.lifetime.mul_f32(rand.random_range(0.5..=1.0));
+
+            let velocity = Vec2::from_angle(angle).rotate(direction) * speed
+                + body_velocity * settings.inherit_velocity;
+
+            commands.spawn((
+                Name::new("Particle"),
+                StateScoped(Screen::Gameplay),
+                Particle {
+                    velocity,
+                    age: Duration::ZERO,
+                    lifetime,
+                    color_start: settings.color_start,
+                    color_end: settings.color_end,
+                },
+                LAYER_PARTICLES,
+                Transform::from_translation(translation.xy().extend(0.0))
+                    .with_rotation(Quat::from_rotation_z(rand.random_range(0.0..PI))),
+                Sprite {
+                    image: image.0.clone(),
+                    color: settings.color_start,
+                    custom_size: Some(Vec2::splat(size)),
+                    ..default()
+                },
+            ));
+        }
+    }
+}
+
+fn update_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    particles: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+) {
+    for (entity, mut particle, mut transform, mut sprite) in particles {
+        particle.age += time.delta();
+
+        if particle.age >= particle.lifetime {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let progress = particle.age.as_secs_f32() / particle.lifetime.as_secs_f32();
+        let remaining = 1.0 - progress;
+
+        transform.translation += (particle.velocity * time.delta_secs()).extend(0.0);
+        transform.scale = Vec3::splat(remaining);
+
+        let color = particle.color_start.mix(&particle.color_end, progress);
+        sprite.color = color.with_alpha(color.alpha() * remaining);
+    }
+}
@@ -1,224 +1,252 @@
-use crate::common::stopwatch::Stopwatch;
+use crate::common::pause::PausableSystems;
+use crate::game::animation::{AnimationFrames, AnimationMode, SpriteAnimation};
 use crate::game::assets::PlanetAssets;
 use crate::game::attraction::Attractor;
+use crate::game::config::GameConfig;
 use crate::game::cv::LAYER_PLANETS;
 use crate::game::shadow::Shadow;
 use crate::game::wiggle::Wiggle;
+use crate::screens::Screen;
+use crate::{AppSystems, MainCamera};
 use avian2d::prelude::{Collider, ColliderDensity, RigidBody};
-use bevy::asset::RenderAssetUsages;
-use bevy::ecs::spawn::SpawnIter;
+use bevy::ecs::spawn::SpawnWith;
 use bevy::prelude::*;
 use bevy::sprite::Anchor;
-use image::{DynamicImage, GenericImageView, ImageBuffer, Pixel, Primitive};
-use std::collections::HashMap;
-use std::ops::Deref;
+
+/// Planets are large, so their shadow falls a bit further.
+const PLANET_SHADOW_HEIGHT: f32 = 24.0;
+
+/// Frame rate of animated planet layers.
+const PLANET_LAYER_FPS: f32 = 8.0;
+
+/// How far a layer can shift due to parallax, relative to the radius of the planet.
+const PARALLAX_MAX_SHIFT: f32 = 0.08;
 
 pub(super) fn plugin(app: &mut App) {
-    app.init_resource::<CropCache>();
+    app.add_systems(
+        Update,
+        apply_planet_density
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::PrePhysics),
+    );
+
+    app.add_systems(
+        Update,
+        move_planet_layers
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::UpdateMarkers)
+            .in_set(PausableSystems),
+    );
 }
 
-pub struct Planet;
+#[derive(Component, Reflect)]
+pub struct Planet {
+    pub radius: f32,
+}
 
-pub fn bundle(
-    assets: &PlanetAssets,
-    crop_cache: &mut CropCache,
-    images: &mut Assets<Image>,
-    radius: f32,
-) -> impl Bundle {
-    let mut layer_bundle = |idx: usize, image: &Handle<Image>| {
-        // crop empty regions from image
-        let cropped = image_crop(crop_cache, images, image).unwrap();
+pub fn bundle(assets: &PlanetAssets, radius: f32) -> impl Bundle {
+    let size = Vec2::splat(2.0 * radius);
 
-        // position & size must be scaled by this much
-        let scale = Vec2::splat(2.0 * radius) / cropped.full_size.as_vec2();
+    let layer_bundle = |idx: usize, layer: &PlanetLayer| {
+        let first = &layer.frames[0];
+        let (offset, frame_size) = first.placement(size);
 
-        // the size of the cropped image
-        let size = cropped.rect.size().as_vec2() * scale;
+        // layers with more than one frame are animated
+        let animation = (layer.frames.len() > 1).then(|| {
+            let frames = AnimationFrames::Cropped {
+                frames: layer.frames.clone(),
+                size,
+            };
 
-        // calculate the offset in position
-        let offset = cropped.rect.min.as_vec2() * vec2(1.0, -1.0) * scale
-            + vec2(-radius, radius)
-            + size * vec2(0.5, -0.5);
+            SpriteAnimation::new(frames, PLANET_LAYER_FPS, AnimationMode::Loop)
+        });
 
-        (
+        let sprite = (
             LAYER_PLANETS.offset_by(idx as i32),
             Wiggle::with_offset(offset),
-            Shadow::default(),
+            Shadow {
+                height: PLANET_SHADOW_HEIGHT,
+                ..default()
+            },
             Sprite {
-                image: cropped.handle.clone(),
-                custom_size: Some(size),
+                image: first.handle.clone(),
+                custom_size: Some(frame_size),
                 anchor: Anchor::Center,
                 ..default()
             },
-        )
+        );
+
+        let pivot = (
+            Name::new("PlanetLayer"),
+            LayerPivot {
+                motion: layer.motion,
+                max_shift: radius * PARALLAX_MAX_SHIFT,
+                angle: 0.0,
+            },
+            Transform::default(),
+            Visibility::Inherited,
+        );
+
+        (pivot, sprite, animation)
     };
 
     let children: Vec<_> = assets
-        .images
+        .layers
         .iter()
         .enumerate()
-        .map(|(idx, image)| layer_bundle(idx, image))
+        .map(|(idx, layer)| layer_bundle(idx, layer))
         .collect();
 
+    let spawn_layers = move |parent: &mut ChildSpawner| {
+        for (pivot, layer, animation) in children {
+            // the layer rotates around the pivot in the center of the planet
+            parent.spawn(pivot).with_children(|parent| {
+                let mut layer = parent.spawn(layer);
+                if let Some(animation) = animation {
+                    layer.insert(animation);
+                }
+            });
+        }
+    };
+
     (
+        Planet { radius },
         RigidBody::Static,
         Collider::circle(radius),
-        ColliderDensity(100000.0),
         Attractor,
         Visibility::Inherited,
-        Children::spawn(SpawnIter(children.into_iter())),
+        Children::spawn(SpawnWith(spawn_layers)),
     )
 }
 
-#[derive(Default, Resource, Reflect, Deref, DerefMut)]
-pub struct CropCache(HashMap<Handle<Image>, Cropped>);
-
+/// An image that was cropped to its visible pixels.
 #[derive(Clone, Reflect)]
 pub struct Cropped {
     pub handle: Handle<Image>,
+    /// The region of the original image that is still visible.
     pub rect: URect,
+    /// Size of the original image.
     pub full_size: UVec2,
 }
 
-fn image_crop(
-    cache: &mut CropCache,
-    images: &mut Assets<Image>,
-    image: &Handle<Image>,
-) -> Result<Cropped> {
-    if let Some(cached) = cache.get(image) {
-        return Ok(cached.clone());
+impl Cropped {
+    /// An image that was not cropped, placed like it is.
+    pub fn uncropped(handle: Handle<Image>) -> Self {
+        // only the ratio of the visible region to the full size matters
+        Self {
+            handle,
+            rect: URect::new(0, 0, 1, 1),
+            full_size: UVec2::ONE,
+        }
     }
 
-    let input = images.get(image).ok_or("failed to find image")?;
-
-    let _watch = Stopwatch::new(format!("Crop image of size {}", input.size()));
-
-    // find first row that is non empty
-    let full_image = input.clone().try_into_dynamic()?;
-
-    let crop_rect = match &full_image {
-        DynamicImage::ImageLuma8(image) => find_crop_rect(image),
-        DynamicImage::ImageLumaA8(image) => find_crop_rect(image),
-        DynamicImage::ImageRgb8(image) => find_crop_rect(image),
-        DynamicImage::ImageRgba8(image) => find_crop_rect(image),
-        DynamicImage::ImageLuma16(image) => find_crop_rect(image),
-        DynamicImage::ImageLumaA16(image) => find_crop_rect(image),
-        DynamicImage::ImageRgb16(image) => find_crop_rect(image),
-        DynamicImage::ImageRgba16(image) => find_crop_rect(image),
-        DynamicImage::ImageRgb32F(image) => find_crop_rect(image),
-        DynamicImage::ImageRgba32F(image) => find_crop_rect(image),
-        _ => Err("unknown image type")?,
-    };
+    /// Returns offset and size of the cropped image, when the uncropped
+    /// image would be drawn centered with the given size.
+    pub fn placement(&self, size: Vec2) -> (Vec2, Vec2) {
+        // position & size must be scaled by this much
+        let scale = size / self.full_size.as_vec2();
 
-    let full_size = UVec2::new(full_image.width(), full_image.height());
+        // the size of the cropped image
+        let cropped_size = self.rect.size().as_vec2() * scale;
 
-    let cropped = if crop_rect.is_empty() || crop_rect.size() == full_size {
-        // we keep the original image
-        Cropped {
-            handle: image.clone(),
-            rect: URect::from_corners(UVec2::ZERO, full_size),
-            full_size,
-        }
-    } else {
-        // take a sub image
-        let crop: DynamicImage = full_image
-            .view(
-                crop_rect.min.x,
-                crop_rect.min.y,
-                crop_rect.width(),
-                crop_rect.height(),
-            )
-            .to_image()
-            .into();
-
-        // convert to bevy image
-        let crop = Image::from_dynamic(
-            crop,
-            input.texture_descriptor.format.is_srgb(),
-            RenderAssetUsages::all(),
-        );
+        // calculate the offset in position
+        let offset = self.rect.min.as_vec2() * vec2(1.0, -1.0) * scale
+            + size * vec2(-0.5, 0.5)
+            + cropped_size * vec2(0.5, -0.5);
 
-        Cropped {
-            handle: images.add(crop),
-            rect: crop_rect,
-            full_size,
-        }
-    };
+        (offset, cropped_size)
+    }
+}
 
-    // also put that into the cache
-    cache.insert(image.clone(), cropped.clone());
+/// A layer of a planet, with more than one frame if it is animated.
+#[derive(Clone, Reflect)]
+pub struct PlanetLayer {
+    pub frames: Vec<Cropped>,
+    pub motion: LayerMotion,
+}
 
-    Ok(cropped)
+/// How a layer of a planet moves relative to the planet.
+#[derive(Clone, Copy, Debug, Default, Reflect)]
+pub struct LayerMotion {
+    /// Rotation around the center of the planet, in radians per second.
+    pub rotation_speed: f32,
+    /// How much the layer shifts away from the camera. Higher layers
+    /// should shift more, as if they were closer to the viewer.
+    pub parallax: f32,
 }
 
-fn find_crop_rect<P, Container>(image: &ImageBuffer<P, Container>) -> URect
-where
-    P: Pixel,
-    Container: Deref<Target = [P::Subpixel]>,
-{
-    // get the first row that has a non-zero alpha component
-    let min_y = image
-        .rows()
-        .enumerate()
-        .find_map(|(y, mut pixels)| pixels.any(alpha_is_non_zero).then_some(y as u32))
-        .unwrap_or_default();
+impl LayerMotion {
+    pub const STILL: Self = Self::new(0.0, 0.0);
 
-    // get the last row that has a non-zero alpha component
-    let max_y = image
-        .rows()
-        .enumerate()
-        .rev()
-        .find_map(|(y, mut pixels)| pixels.any(alpha_is_non_zero).then_some(y as u32))
-        .unwrap_or_default();
+    pub const fn new(rotation_speed: f32, parallax: f32) -> Self {
+        Self {
+            rotation_speed,
+            parallax,
+        }
+    }
+}
 
-    // get the first column that has a non zero alpha component
-    let min_x = columns(image)
-        .enumerate()
-        .find_map(|(x, mut pixels)| pixels.any(alpha_is_non_zero).then_some(x as u32))
-        .unwrap_or_default();
+/// Applies the density from the config to new planets and whenever the config changes.
+fn apply_planet_density(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    planets: Query<(Entity, Ref<Planet>)>,
+) {
+    for (entity, planet) in planets {
+        if config.is_changed() || planet.is_added() {
+            commands
+                .entity(entity)
+                .insert(ColliderDensity(config.planet_density));
+        }
+    }
+}
 
-    // get the last column that has a non zero alpha component
-    let max_x = columns(image)
-        .enumerate()
-        .rev()
-        .find_map(|(x, mut pixels)| pixels.any(alpha_is_non_zero).then_some(x as u32))
-        .unwrap_or_default();
+/// Rotates and shifts a layer of a planet, see [`LayerMotion`].
+#[derive(Component)]
+struct LayerPivot {
+    motion: LayerMotion,
+    max_shift: f32,
+    angle: f32,
+}
 
-    URect::new(min_x, min_y, max_x, max_y)
+fn move_planet_layers(
+    time: Res<Time>,
+    camera: Single<&Transform, With<MainCamera>>,
+    planets: Query<&Transform, (With<Planet>, Without<MainCamera>)>,
+    pivots: Query<
+        (&mut LayerPivot, &mut Transform, &ChildOf),
+        (Without<Planet>, Without<MainCamera>),
+    >,
+) {
+    let camera = camera.translation.xy();
+
+    for (mut pivot, mut transform, child_of) in pivots {
+        let Ok(planet) = planets.get(child_of.parent()) else {
+            continue;
+        };
+
+        pivot.angle += pivot.motion.rotation_speed * time.delta_secs();
+
+        // layers shift away from the camera, like something closer to the viewer would
+        let shift = ((planet.translation.xy() - camera) * pivot.motion.parallax)
+            .clamp_length_max(pivot.max_shift);
+
+        transform.translation.x = shift.x;
+        transform.translation.y = shift.y;
+        transform.rotation = Quat::from_rotation_z(pivot.angle);
+    }
 }
 
-#[inline]
-fn alpha_is_non_zero<P>(pixel: &P) -> bool
-where
-    P: Pixel,
-{
-    // assume full alpha by default
-    let mut is_non_zero: bool = false;
-
-    pixel.map_with_alpha(
-        |value| value,
-        |a| {
-            is_non_zero = a != P::Subpixel::DEFAULT_MIN_VALUE;
-            a
-        },
-    );
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    is_non_zero
-}
+    #[test]
+    fn uncropped_image_covers_the_full_size() {
+        let cropped = Cropped::uncropped(Handle::default());
+        let (offset, size) = cropped.placement(vec2(256.0, 128.0));
 
-fn columns<P, Container>(
-    image: &ImageBuffer<P, Container>,
-) -> impl DoubleEndedIterator<Item = impl Iterator<Item = &P>> + ExactSizeIterator
-where
-    P: Pixel,
-    Container: Deref<Target = [P::Subpixel]>,
-{
-    let range = 0..image.width();
-
-    range.map(move |x| {
-        image
-            .pixels()
-            .skip(x as usize)
-            .step_by(image.width() as usize)
-    })
+        assert_eq!(offset, Vec2::ZERO);
+        assert_eq!(size, vec2(256.0, 128.0));
+    }
 }
@@ -1,10 +1,11 @@
-use crate::game;
 use crate::game::attraction::Attractable;
 use crate::game::cv::LAYER_ROCKET;
 use crate::game::input::{Input, InputActive, OnThurst};
 use crate::game::rocket;
-use crate::game::rocket::{Fuel, FuelTank};
-use avian2d::prelude::{ExternalForce, LinearVelocity};
+use crate::game::rocket::{RocketDef, Stages};
+use crate::game::trail::Trail;
+use crate::game::trajectory::PredictedPath;
+use avian2d::prelude::{CollisionEventsEnabled, ExternalForce, LinearVelocity};
 use bevy::prelude::*;
 use std::time::Duration;
 
@@ -21,15 +22,17 @@ pub struct Thrust {
     pub force: Vec2,
 }
 
-pub fn bundle(assets: &game::Assets) -> impl Bundle {
+pub fn bundle(rocket: &RocketDef) -> impl Bundle {
     (
-        rocket::bundle(assets),
+        rocket::bundle(rocket),
         LAYER_ROCKET,
         LinearVelocity::ZERO,
+        CollisionEventsEnabled,
         ExternalForce::ZERO.with_persistence(false),
         Attractable,
         Player,
-        FuelTank::full(Fuel::from_secs(20)),
+        PredictedPath::default(),
+        Trail::default(),
         Input,
     )
 }
@@ -55,10 +58,12 @@ pub fn reset_time_after_input(
 pub fn handle_on_thrust(
     trigger: Trigger<OnThurst>,
     mut commands: Commands,
-    player: Single<Entity, With<Player>>,
+    player: Single<(Entity, &Stages), With<Player>>,
 ) {
-    commands.entity(player.into_inner()).insert(Thrust {
-        force: trigger.direction * 100_000.0,
+    let (player, stages) = player.into_inner();
+
+    commands.entity(player).insert(Thrust {
+        force: trigger.direction * stages.active().thrust,
         remaining: trigger.duration,
     });
 }
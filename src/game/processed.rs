@@ -0,0 +1,59 @@
+//! Lookup tables for the images preprocessed by `gravitate preprocess`,
+//! see `crate::preprocess`.
+
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+/// Where the manifest is written to, relative to the assets directory.
+pub const MANIFEST_PATH: &str = "processed/manifest.ron";
+
+/// The manifest is compiled into the game, so images can be looked up while spawning.
+static MANIFEST: LazyLock<ProcessedManifest> = LazyLock::new(|| {
+    ron::from_str(include_str!("../../assets/processed/manifest.ron"))
+        .expect("valid manifest of preprocessed images")
+});
+
+/// Describes all images written by the preprocessing.
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct ProcessedManifest {
+    pub crops: Vec<ProcessedCrop>,
+    pub shadows: Vec<ProcessedShadow>,
+}
+
+/// A planet layer that was cropped to its visible pixels.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProcessedCrop {
+    /// Path of the original image.
+    pub source: String,
+    /// Path of the cropped image.
+    pub image: String,
+    /// The cropped region within the original image, as `[min_x, min_y, max_x, max_y]`.
+    pub rect: [u32; 4],
+    pub full_size: [u32; 2],
+}
+
+/// A pre-blurred shadow for an image.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProcessedShadow {
+    pub image: String,
+    pub shadow: String,
+    pub sigma: f32,
+}
+
+impl ProcessedManifest {
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+}
+
+pub fn crop(source: &str) -> Option<&'static ProcessedCrop> {
+    MANIFEST.crops.iter().find(|crop| crop.source == source)
+}
+
+pub fn shadow(image: &str, sigma: f32) -> Option<&'static str> {
+    MANIFEST
+        .shadows
+        .iter()
+        .find(|shadow| shadow.image == image && shadow.sigma == sigma)
+        .map(|shadow| shadow.shadow.as_str())
+}
@@ -1,16 +1,30 @@
 use crate::common::pause::PausableSystems;
+use crate::common::rand::Rand;
+use crate::game::animation::{AnimationFrames, AnimationMode, SpriteAnimation};
+use crate::game::assets::FLAME_FRAMES;
+use crate::game::attraction::Attractable;
+use crate::game::config::GameConfig;
 use crate::game::cv::{
-    LAYER_OFFSET_ROCKET_FIN_BG, LAYER_OFFSET_ROCKET_FIN_FG, LAYER_OFFSET_ROCKET_PLUME,
+    COLOR_EXHAUST_COLD, COLOR_EXHAUST_HOT, LAYER_DEBRIS, LAYER_OFFSET_ROCKET_FIN_BG,
+    LAYER_OFFSET_ROCKET_FIN_FG, LAYER_OFFSET_ROCKET_STAGE,
 };
+use crate::game::input::OnJettison;
+use crate::game::level::LevelRules;
+use crate::game::particles::{ParticleEmitter, ParticleSettings};
 use crate::game::player::Thrust;
 use crate::game::shadow::Shadow;
 use crate::game::wiggle::Wiggle;
 use crate::screens::Screen;
 use crate::{AppSystems, game};
-use avian2d::prelude::{Collider, ExternalForce, RigidBody};
+use avian2d::prelude::{
+    AngularVelocity, Collider, ExternalForce, LinearVelocity, Mass, NoAutoMass, RigidBody,
+};
+use bevy::ecs::spawn::SpawnWith;
 use bevy::prelude::*;
 use bevy::sprite::Anchor;
-use std::f32::consts::PI;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::{FRAC_PI_4, PI};
 use std::time::Duration;
 
 pub(super) fn plugin(app: &mut App) {
@@ -18,13 +32,21 @@ pub(super) fn plugin(app: &mut App) {
         Update,
         (
             apply_thrust,
-            apply_plume_visibility,
+            apply_exhaust_intensity,
             rotate_direction_of_thrust,
+            update_rocket_mass.after(apply_thrust),
         )
             .run_if(in_state(Screen::Gameplay))
             .in_set(AppSystems::PrePhysics)
             .in_set(PausableSystems),
     );
+
+    app.add_systems(
+        Update,
+        apply_config_thrust.run_if(resource_changed::<GameConfig>),
+    );
+
+    app.add_observer(jettison_stage);
 }
 
 #[derive(Component)]
@@ -33,6 +55,289 @@ pub struct Rocket;
 #[derive(Component)]
 pub struct Plume;
 
+/// The flickering engine flame, a child of the [`Plume`].
+#[derive(Component)]
+struct Flame;
+
+/// Frame rate of the flickering flame.
+const FLAME_FPS: f32 = 16.0;
+
+/// A spent stage that was dropped from a rocket.
+#[derive(Component)]
+pub struct Debris;
+
+/// Describes how a rocket is built and which stages it burns through.
+#[derive(Clone)]
+pub struct RocketDef {
+    /// Mass of the rocket without any of its stages.
+    pub dry_mass: f32,
+    pub collider: Collider,
+    pub parts: RocketParts,
+    /// The stages of the rocket in the order they are burned.
+    pub stages: Vec<StageDef>,
+    /// Fuel for the reaction control system, used to turn faster.
+    pub rcs_fuel: Fuel,
+}
+
+/// The images a rocket is drawn from.
+#[derive(Clone)]
+pub struct RocketParts {
+    pub base: Handle<Image>,
+    pub fin_bg: Handle<Image>,
+    pub fin_fg: Handle<Image>,
+    pub flame: Handle<Image>,
+    pub flame_layout: Handle<TextureAtlasLayout>,
+}
+
+#[derive(Clone, Reflect)]
+pub struct StageDef {
+    /// Force applied while this stage is burning.
+    pub thrust: f32,
+    pub fuel: Fuel,
+    /// Structural mass of the stage, dropped when the stage is jettisoned.
+    pub mass: f32,
+    /// Mass of one second worth of fuel, only used if fuel has mass in the current level.
+    pub fuel_mass: f32,
+    /// Position of the plume while this stage is burning.
+    pub plume_offset: Vec2,
+    pub sprite: Option<StageSprite>,
+}
+
+#[derive(Clone, Reflect)]
+pub struct StageSprite {
+    pub image: Handle<Image>,
+    pub offset: Vec2,
+    pub size: Vec2,
+    /// Animates the sprite, e.g. for flickering flames.
+    pub animation: Option<SpriteAnimation>,
+}
+
+impl RocketParts {
+    pub fn from_assets(assets: &game::Assets) -> Self {
+        Self {
+            base: assets.rocket_base.clone(),
+            fin_bg: assets.rocket_fin_bg.clone(),
+            fin_fg: assets.rocket_fin_fg.clone(),
+            flame: assets.flame.clone(),
+            flame_layout: assets.flame_layout.clone(),
+        }
+    }
+}
+
+/// The rockets a level can give the player.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum RocketKind {
+    /// A booster stage below the main body, see [`RocketDef::two_stage`].
+    #[default]
+    TwoStage,
+    /// Just the main body, see [`RocketDef::single_stage`].
+    SingleStage,
+}
+
+impl RocketDef {
+    pub fn new(kind: RocketKind, assets: &game::Assets, config: &GameConfig) -> Self {
+        match kind {
+            RocketKind::TwoStage => Self::two_stage(assets, config),
+            RocketKind::SingleStage => Self::single_stage(assets, config),
+        }
+    }
+
+    /// A rocket with a booster stage below the main body.
+    pub fn two_stage(assets: &game::Assets, config: &GameConfig) -> Self {
+        let collider = Collider::capsule(32., 48.);
+
+        // keep the total mass of the rocket at what the collider would give us
+        let total_mass = Mass::from_shape(&collider, 1.0).0;
+
+        Self {
+            dry_mass: total_mass * 0.75,
+            collider,
+            parts: RocketParts::from_assets(assets),
+            stages: vec![
+                StageDef {
+                    thrust: config.stage(0).thrust,
+                    fuel: Fuel::new(Duration::from_secs_f32(config.stage(0).fuel_secs)),
+                    mass: total_mass * 0.25,
+                    fuel_mass: total_mass * 0.025,
+                    plume_offset: vec2(0., -112.),
+                    sprite: Some(StageSprite {
+                        image: assets.rocket_booster.clone(),
+                        offset: vec2(0., -80.),
+                        size: vec2(64., 64.),
+                        animation: None,
+                    }),
+                },
+                StageDef {
+                    thrust: config.stage(1).thrust,
+                    fuel: Fuel::new(Duration::from_secs_f32(config.stage(1).fuel_secs)),
+                    mass: 0.0,
+                    fuel_mass: total_mass * 0.025,
+                    plume_offset: vec2(0., -56.),
+                    sprite: None,
+                },
+            ],
+            rcs_fuel: Fuel::new(Duration::from_secs_f32(config.rcs_fuel_secs)),
+        }
+    }
+
+    /// The main body of the rocket without a booster. It is as heavy as the
+    /// two stage rocket after dropping its booster.
+    pub fn single_stage(assets: &game::Assets, config: &GameConfig) -> Self {
+        let collider = Collider::capsule(32., 48.);
+        let total_mass = Mass::from_shape(&collider, 1.0).0;
+
+        Self {
+            dry_mass: total_mass * 0.75,
+            collider,
+            parts: RocketParts::from_assets(assets),
+            stages: vec![StageDef {
+                thrust: config.stage(0).thrust,
+                fuel: Fuel::new(Duration::from_secs_f32(config.stage(0).fuel_secs)),
+                mass: 0.0,
+                fuel_mass: total_mass * 0.025,
+                plume_offset: vec2(0., -56.),
+                sprite: None,
+            }],
+            rcs_fuel: Fuel::new(Duration::from_secs_f32(config.rcs_fuel_secs)),
+        }
+    }
+}
+
+/// The stages of a rocket and the index of the one that is currently burning.
+#[derive(Component, Reflect)]
+pub struct Stages {
+    stages: Vec<StageDef>,
+    active: usize,
+}
+
+impl Stages {
+    pub fn active(&self) -> &StageDef {
+        &self.stages[self.active]
+    }
+
+    /// Index of the stage that is currently burning.
+    pub fn active_index(&self) -> usize {
+        self.active
+    }
+
+    /// Thrust of the strongest stage, attached or not.
+    pub fn max_thrust(&self) -> f32 {
+        self.stages
+            .iter()
+            .map(|stage| stage.thrust)
+            .fold(0.0, f32::max)
+    }
+
+    pub fn can_jettison(&self) -> bool {
+        self.active + 1 < self.stages.len()
+    }
+
+    /// The structural mass of all stages still attached.
+    pub fn mass(&self) -> f32 {
+        self.stages[self.active..]
+            .iter()
+            .map(|stage| stage.mass)
+            .sum()
+    }
+
+    /// The mass of the fuel left in the active tank and all stages still attached.
+    pub fn fuel_mass(&self, tank: &FuelTank) -> f32 {
+        let active = tank.remaining.as_secs() * self.active().fuel_mass;
+
+        let upcoming: f32 = self.stages[self.active + 1..]
+            .iter()
+            .map(|stage| stage.fuel.as_secs() * stage.fuel_mass)
+            .sum();
+
+        active + upcoming
+    }
+}
+
+/// Fuel of the reaction control system.
+#[derive(Component, Reflect)]
+pub struct RcsTank(pub FuelTank);
+
+/// How a rocket turns into the direction of thrust.
+#[derive(Clone, Copy, Default, Debug, Reflect, Serialize, Deserialize)]
+pub enum AttitudeControl {
+    /// The rocket turns for free and thrust is applied in the target direction right away.
+    #[default]
+    Free,
+
+    /// Thrust is applied along the facing of the rocket, which turns with a limited
+    /// rate in radians per second. While there is RCS fuel left, it is burned to
+    /// turn with the faster `rcs_turn_rate`.
+    Realistic { turn_rate: f32, rcs_turn_rate: f32 },
+}
+
+/// Rockets closer than this to the direction of thrust count as facing it, in radians.
+const FACING_TOLERANCE: f32 = 0.01;
+
+impl AttitudeControl {
+    /// Turning with a limited rate, which RCS fuel speeds up.
+    pub const REALISTIC: AttitudeControl = AttitudeControl::Realistic {
+        turn_rate: FRAC_PI_4,
+        rcs_turn_rate: PI,
+    };
+
+    /// The rate the rocket turns at, or `None` if turning is free.
+    pub fn turn_rate(&self, rcs: Option<&RcsTank>) -> Option<f32> {
+        let has_rcs = rcs.is_some_and(|rcs| rcs.0.remaining.as_secs() > 0.0);
+        self.turn_rate_with_rcs(has_rcs)
+    }
+
+    /// The rate the rocket turns at, depending on whether there is RCS fuel left.
+    pub fn turn_rate_with_rcs(&self, has_rcs: bool) -> Option<f32> {
+        match *self {
+            AttitudeControl::Free => None,
+            AttitudeControl::Realistic {
+                turn_rate,
+                rcs_turn_rate,
+            } => Some(if has_rcs { rcs_turn_rate } else { turn_rate }),
+        }
+    }
+
+    /// Whether a rocket with the given rotation can push into `direction`.
+    /// Always true if turning is free.
+    pub fn is_facing(&self, rotation: Quat, direction: Vec2) -> bool {
+        match self {
+            AttitudeControl::Free => true,
+            AttitudeControl::Realistic { .. } => {
+                facing(rotation).angle_to(direction).abs() <= FACING_TOLERANCE
+            }
+        }
+    }
+
+    /// How long it takes for a rocket with the given rotation to face into `direction`.
+    pub fn turn_duration(
+        &self,
+        rotation: Quat,
+        direction: Vec2,
+        rcs: Option<&RcsTank>,
+    ) -> Duration {
+        let Some(turn_rate) = self.turn_rate(rcs) else {
+            return Duration::ZERO;
+        };
+
+        let angle = facing(rotation).angle_to(direction).abs();
+        Duration::from_secs_f32(angle / turn_rate)
+    }
+}
+
+/// The direction a rocket with the given rotation is facing.
+pub fn facing(rotation: Quat) -> Vec2 {
+    // for the rocket sprites, forwards is up
+    (rotation * Vec3::Y).xy()
+}
+
+/// Mass of the rocket without any stages or fuel.
+#[derive(Component, Reflect)]
+pub struct DryMass(pub f32);
+
+/// Marks the sprite of a stage with the index of the stage.
+#[derive(Component)]
+struct StagePart(usize);
+
 #[derive(Component, Reflect)]
 pub struct FuelTank {
     pub capacity: Fuel,
@@ -50,6 +355,10 @@ impl FuelTank {
     pub fn burn(&mut self, amount: Duration) {
         self.remaining.burn(amount);
     }
+
+    pub fn refuel(&mut self, amount: Duration) {
+        self.remaining.refill(amount, self.capacity);
+    }
 }
 
 #[derive(Copy, Clone, Debug, Reflect)]
@@ -68,43 +377,72 @@ impl Fuel {
         self.0.as_secs_f32()
     }
 
+    pub fn as_duration(&self) -> Duration {
+        self.0
+    }
+
     pub fn burn(&mut self, amount: Duration) {
         self.0 = self.0.saturating_sub(amount);
     }
+
+    pub fn refill(&mut self, amount: Duration, capacity: Fuel) {
+        self.0 = (self.0 + amount).min(capacity.0);
+    }
 }
 
-pub fn bundle(assets: &game::Assets) -> impl Bundle {
+pub fn bundle(def: &RocketDef) -> impl Bundle {
     let fin_offset = vec2(0., -40.);
+    let parts = &def.parts;
+
+    let stages = Stages {
+        stages: def.stages.clone(),
+        active: 0,
+    };
+
+    let stage_sprites: Vec<_> = def
+        .stages
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, stage)| Some((idx, stage.sprite.clone()?)))
+        .collect();
+
+    let spawn_stage_parts = move |parent: &mut ChildSpawner| {
+        for (idx, mut sprite) in stage_sprites {
+            let animation = sprite.animation.take();
+            spawn_animated(parent, stage_part_bundle(idx, sprite), animation);
+        }
+    };
 
     // spawn the player
     (
         RigidBody::Dynamic,
-        Collider::capsule(32., 48.),
+        def.collider.clone(),
+        DryMass(def.dry_mass),
+        Mass(def.dry_mass + stages.mass()),
+        NoAutoMass,
+        FuelTank::full(stages.active().fuel),
+        RcsTank(FuelTank::full(def.rcs_fuel)),
         Rocket,
         Visibility::Inherited,
-        children![
-            (
+        Children::spawn((
+            Spawn((
                 Name::new("PlumeGroup"),
                 Plume,
-                Transform::from_xyz(0., -56.0, 0.),
-                Visibility::Hidden,
-                children![
-                    plume_bundle(assets, 0),
-                    plume_bundle(assets, 1),
-                    plume_bundle(assets, 2),
-                ],
-            ),
-            (
+                Transform::from_translation(stages.active().plume_offset.extend(0.)),
+                exhaust_emitter(),
+                children![flame_bundle(parts)],
+            )),
+            Spawn((
                 Name::new("Body"),
                 Shadow::default(),
                 Wiggle::default(),
                 Sprite {
-                    image: assets.rocket_base.clone(),
+                    image: parts.base.clone(),
                     anchor: Anchor::Center,
                     ..default()
                 },
-            ),
-            (
+            )),
+            Spawn((
                 Name::new("FinBG"),
                 Shadow::default(),
                 Wiggle {
@@ -115,12 +453,12 @@ pub fn bundle(assets: &game::Assets) -> impl Bundle {
                 },
                 LAYER_OFFSET_ROCKET_FIN_BG,
                 Sprite {
-                    image: assets.rocket_fin_bg.clone(),
+                    image: parts.fin_bg.clone(),
                     anchor: Anchor::Center,
                     ..default()
                 },
-            ),
-            (
+            )),
+            Spawn((
                 Name::new("FinFG"),
                 Shadow::default(),
                 Wiggle {
@@ -131,37 +469,141 @@ pub fn bundle(assets: &game::Assets) -> impl Bundle {
                 },
                 LAYER_OFFSET_ROCKET_FIN_FG,
                 Sprite {
-                    image: assets.rocket_fin_fg.clone(),
+                    image: parts.fin_fg.clone(),
                     anchor: Anchor::Center,
                     ..default()
                 },
-            ),
-        ],
+            )),
+            SpawnWith(spawn_stage_parts),
+        )),
+        stages,
+    )
+}
+
+/// Spawns a sprite as a child, with an animation if there is one.
+fn spawn_animated(
+    parent: &mut ChildSpawner,
+    bundle: impl Bundle,
+    animation: Option<SpriteAnimation>,
+) {
+    let mut entity = parent.spawn(bundle);
+    if let Some(animation) = animation {
+        entity.insert(animation);
+    }
+}
+
+fn stage_part_bundle(idx: usize, sprite: StageSprite) -> impl Bundle {
+    (
+        Name::new("Stage"),
+        StagePart(idx),
+        Shadow::default(),
+        Wiggle {
+            offset: sprite.offset,
+            scale_rotation: 0.5_f32.to_radians(),
+            scale_transform: 0.5,
+            ..default()
+        },
+        LAYER_OFFSET_ROCKET_STAGE,
+        Sprite {
+            image: sprite.image,
+            custom_size: Some(sprite.size),
+            anchor: Anchor::Center,
+            ..default()
+        },
+    )
+}
+
+fn debris_bundle(
+    mut sprite: StageSprite,
+    mass: f32,
+    transform: Transform,
+    velocity: Vec2,
+    spin: f32,
+) -> impl Bundle {
+    (
+        Name::new("Debris"),
+        StateScoped(Screen::Gameplay),
+        Debris,
+        transform,
+        LAYER_DEBRIS,
+        RigidBody::Dynamic,
+        Collider::rectangle(sprite.size.x, sprite.size.y),
+        Mass(mass.max(1.0)),
+        NoAutoMass,
+        LinearVelocity(velocity),
+        AngularVelocity(spin),
+        ExternalForce::ZERO.with_persistence(false),
+        Attractable,
+        Visibility::Inherited,
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            let animation = sprite.animation.take();
+            spawn_animated(parent, debris_sprite_bundle(sprite), animation);
+        })),
     )
 }
 
-fn plume_bundle(assets: &game::Assets, idx: i32) -> impl Bundle {
+fn debris_sprite_bundle(sprite: StageSprite) -> impl Bundle {
     (
-        Name::new("Plume"),
+        Name::new("DebrisSprite"),
         Shadow::default(),
         Wiggle {
-            scale_rotation: 10f32.to_radians(),
+            scale_rotation: 0.5_f32.to_radians(),
+            scale_transform: 0.5,
             ..default()
         },
-        LAYER_OFFSET_ROCKET_PLUME.offset_by(idx),
         Sprite {
-            image: assets.plume[idx as usize].clone(),
+            image: sprite.image,
+            custom_size: Some(sprite.size),
             anchor: Anchor::Center,
             ..default()
         },
     )
 }
 
+fn flame_bundle(parts: &RocketParts) -> impl Bundle {
+    let frames = AnimationFrames::Atlas {
+        first: 0,
+        last: FLAME_FRAMES as usize - 1,
+    };
+
+    (
+        Name::new("Flame"),
+        Flame,
+        LAYER_OFFSET_ROCKET_STAGE,
+        Visibility::Hidden,
+        Sprite {
+            image: parts.flame.clone(),
+            texture_atlas: Some(TextureAtlas::from(parts.flame_layout.clone())),
+            anchor: Anchor::TopCenter,
+            ..default()
+        },
+        SpriteAnimation::new(frames, FLAME_FPS, AnimationMode::PingPong),
+    )
+}
+
+fn exhaust_emitter() -> ParticleEmitter {
+    ParticleEmitter::continuous(
+        ParticleSettings {
+            direction: Vec2::NEG_Y,
+            spread: 12f32.to_radians(),
+            speed: 300.0..450.0,
+            lifetime: Duration::from_secs_f32(0.6),
+            size: 16.0..32.0,
+            color_start: COLOR_EXHAUST_HOT,
+            color_end: COLOR_EXHAUST_COLD,
+            inherit_velocity: 1.0,
+        },
+        90.0,
+    )
+}
+
 fn apply_thrust(
     mut commands: Commands,
+    rules: Res<LevelRules>,
     rocket: Query<
         (
             Entity,
+            &Transform,
             &mut Thrust,
             &mut ExternalForce,
             Option<&mut FuelTank>,
@@ -170,7 +612,12 @@ fn apply_thrust(
     >,
     time: Res<Time>,
 ) {
-    for (entity, mut thrust, mut external_force, fuel_tank) in rocket {
+    for (entity, transform, mut thrust, mut external_force, fuel_tank) in rocket {
+        // the burn only starts once the rocket has turned into the direction of thrust
+        if !rules.attitude.is_facing(transform.rotation, thrust.force) {
+            continue;
+        }
+
         // reduce remaining thrust
         thrust.remaining = thrust.remaining.saturating_sub(time.delta());
 
@@ -185,42 +632,332 @@ fn apply_thrust(
             commands.entity(entity).remove::<Thrust>();
         }
 
+        let force = match rules.attitude {
+            AttitudeControl::Free => thrust.force,
+
+            // the engine can only push along the direction the rocket is facing
+            AttitudeControl::Realistic { .. } => facing(transform.rotation) * thrust.force.length(),
+        };
+
         // apply last bit of thrust
-        external_force.apply_force(thrust.force);
+        external_force.apply_force(force);
+    }
+}
+
+/// Lets the thrust of each stage be tuned while flying.
+fn apply_config_thrust(config: Res<GameConfig>, rockets: Query<&mut Stages>) {
+    for mut stages in rockets {
+        for (idx, stage) in stages.stages.iter_mut().enumerate() {
+            stage.thrust = config.stage(idx).thrust;
+        }
+    }
+}
+
+fn update_rocket_mass(
+    rules: Res<LevelRules>,
+    rockets: Query<(&mut Mass, &DryMass, &Stages, &FuelTank), With<Rocket>>,
+) {
+    for (mut mass, dry_mass, stages, tank) in rockets {
+        let mut target = dry_mass.0 + stages.mass();
+
+        if rules.fuel_has_mass {
+            target += stages.fuel_mass(tank);
+        }
+
+        // avian recomputes the mass properties of the body once the mass changes
+        mass.set_if_neq(Mass(target));
     }
 }
 
 fn rotate_direction_of_thrust(
-    rocket: Query<(&mut Transform, &Thrust), With<Rocket>>,
+    rules: Res<LevelRules>,
+    rocket: Query<
+        (
+            &mut Transform,
+            &mut AngularVelocity,
+            &Thrust,
+            Option<&mut RcsTank>,
+        ),
+        With<Rocket>,
+    >,
     time: Res<Time>,
 ) {
-    for (mut transform, thrust) in rocket {
-        // the sprites are not oriented correctly, for the rocket, forwards is up.
-        // we need to fix this by applying an offset to the intended rotation
-        let offset = -PI / 2.0;
+    for (mut transform, mut angular_velocity, thrust, mut rcs) in rocket {
+        let Some(turn_rate) = rules.attitude.turn_rate(rcs.as_deref()) else {
+            // the sprites are not oriented correctly, for the rocket, forwards is up.
+            // we need to fix this by applying an offset to the intended rotation
+            let offset = -PI / 2.0;
+
+            // target a rotation into the direction of the force to be applied
+            let target = Quat::from_rotation_z(thrust.force.to_angle() + offset);
+
+            transform
+                .rotation
+                .smooth_nudge(&target, 5.0, time.delta_secs());
+
+            continue;
+        };
+
+        // the attitude control holds the rocket steady, e.g. after a bounce. otherwise a
+        // spin faster than the turn rate would keep the rocket from ever facing the target
+        angular_velocity.set_if_neq(AngularVelocity::ZERO);
 
-        // target a rotation into the direction of the force to be applied
-        let target = Quat::from_rotation_z(thrust.force.to_angle() + offset);
+        // turn as far as we can this frame
+        let max_step = turn_rate * time.delta_secs();
+        let angle = facing(transform.rotation).angle_to(thrust.force);
+        let step = angle.clamp(-max_step, max_step);
 
-        transform
-            .rotation
-            .smooth_nudge(&target, 5.0, time.delta_secs());
+        if step == 0.0 {
+            continue;
+        }
+
+        transform.rotate_z(step);
+
+        // turning uses up rcs fuel
+        if let Some(rcs) = rcs.as_mut() {
+            rcs.0.burn(time.delta().mul_f32(step.abs() / max_step));
+        }
     }
 }
 
-fn apply_plume_visibility(
-    rockets: Query<Has<Thrust>, With<Rocket>>,
-    plumes: Query<(&ChildOf, &mut Visibility), With<Plume>>,
+fn apply_exhaust_intensity(
+    rules: Res<LevelRules>,
+    rockets: Query<(&Transform, Option<&Thrust>, &Stages), With<Rocket>>,
+    plumes: Query<(&ChildOf, &Children, &mut ParticleEmitter), With<Plume>>,
+    mut flames: Query<(&mut Transform, &mut Visibility), (With<Flame>, Without<Rocket>)>,
 ) {
-    for (child_of, mut plume_visibility) in plumes {
-        let has_thrust = rockets.get(child_of.parent()).unwrap_or_default();
+    for (child_of, children, mut emitter) in plumes {
+        let Ok((transform, thrust, stages)) = rockets.get(child_of.parent()) else {
+            continue;
+        };
 
-        let visibility = if has_thrust {
-            Visibility::Inherited
-        } else {
-            Visibility::Hidden
+        // the exhaust of the strongest stage is the largest
+        let intensity = match thrust {
+            Some(thrust)
+                if stages.max_thrust() > 0.0
+                    && rules.attitude.is_facing(transform.rotation, thrust.force) =>
+            {
+                (thrust.force.length() / stages.max_thrust()).clamp(0.0, 1.0)
+            }
+            _ => 0.0,
         };
 
-        plume_visibility.set_if_neq(visibility);
+        emitter.intensity = intensity;
+
+        // the flame grows with the thrust, like the exhaust
+        let mut iter = flames.iter_many_mut(children);
+        while let Some((mut transform, mut visibility)) = iter.fetch_next() {
+            if intensity > 0.0 {
+                transform.scale.y = intensity;
+                visibility.set_if_neq(Visibility::Inherited);
+            } else {
+                visibility.set_if_neq(Visibility::Hidden);
+            }
+        }
+    }
+}
+
+fn jettison_stage(
+    trigger: Trigger<OnJettison>,
+    mut commands: Commands,
+    mut rockets: Query<
+        (
+            &mut Stages,
+            &mut FuelTank,
+            &Transform,
+            &LinearVelocity,
+            &Children,
+        ),
+        With<Rocket>,
+    >,
+    stage_parts: Query<&StagePart>,
+    mut plumes: Query<&mut Transform, (With<Plume>, Without<Rocket>)>,
+    mut rand: ResMut<Rand>,
+) {
+    let Ok((mut stages, mut tank, transform, velocity, children)) =
+        rockets.get_mut(trigger.target())
+    else {
+        return;
+    };
+
+    if !stages.can_jettison() {
+        info!("No stage left to jettison");
+        return;
+    }
+
+    let dropped = stages.active().clone();
+    let dropped_idx = stages.active;
+
+    stages.active += 1;
+    *tank = FuelTank::full(stages.active().fuel);
+
+    info!("Jettisoned stage {}", dropped_idx);
+
+    for child in children.iter() {
+        if let Ok(mut plume_transform) = plumes.get_mut(child) {
+            let offset = stages.active().plume_offset;
+            plume_transform.translation.x = offset.x;
+            plume_transform.translation.y = offset.y;
+        }
+
+        if stage_parts
+            .get(child)
+            .is_ok_and(|part| part.0 == dropped_idx)
+        {
+            commands.entity(child).despawn();
+        }
+    }
+
+    let Some(sprite) = dropped.sprite else {
+        return;
+    };
+
+    // drop the debris where the stage was attached and push it away from the rocket
+    let position = transform.transform_point(sprite.offset.extend(0.0));
+    let backwards = transform.rotation * Vec3::NEG_Y;
+
+    commands.spawn(debris_bundle(
+        sprite,
+        dropped.mass,
+        Transform::from_translation(position).with_rotation(transform.rotation),
+        velocity.0 + backwards.xy() * 32.0,
+        rand.random_range(-1.0..1.0),
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{TestApp, assert_near};
+
+    /// One step of the physics, the precision of anything measured in time.
+    const STEP: f32 = 1.0 / 64.0;
+
+    #[test]
+    fn thrust_burns_fuel_and_moves_rocket() {
+        let mut app = TestApp::new();
+        let rocket = app.spawn_rocket(Vec2::ZERO);
+        let capacity = app.fuel(rocket);
+
+        app.thrust(rocket, Vec2::Y, 2.0);
+        app.advance_secs(1.0);
+
+        assert_near(app.fuel(rocket), capacity - 1.0, STEP);
+        assert!(app.position(rocket).y > 0.0);
+        assert!(app.world().get::<Thrust>(rocket).is_some());
+        assert_eq!(flame_visibility(&mut app), Visibility::Inherited);
+
+        // the burn stops after two seconds, the rocket keeps its speed
+        app.advance_secs(2.0);
+
+        assert_near(app.fuel(rocket), capacity - 2.0, STEP);
+        assert!(app.world().get::<Thrust>(rocket).is_none());
+        assert!(app.velocity(rocket).y > 0.0);
+        assert_eq!(flame_visibility(&mut app), Visibility::Hidden);
+    }
+
+    fn flame_visibility(app: &mut TestApp) -> Visibility {
+        let mut flames = app.world_mut().query_filtered::<&Visibility, With<Flame>>();
+
+        *flames.single(app.world()).unwrap()
+    }
+
+    #[test]
+    fn rocket_gets_lighter_while_burning_if_fuel_has_mass() {
+        let mut app = TestApp::new();
+        app.world_mut().resource_mut::<LevelRules>().fuel_has_mass = true;
+
+        let rocket = app.spawn_rocket(Vec2::ZERO);
+        app.step();
+
+        let mass = |app: &TestApp| app.world().get::<Mass>(rocket).unwrap().0;
+        let full = mass(&app);
+
+        app.thrust(rocket, Vec2::Y, 2.0);
+        app.advance_secs(2.0);
+
+        let stages = app.world().get::<Stages>(rocket).unwrap();
+        let burned = 2.0 * stages.active().fuel_mass;
+        assert_near(mass(&app), full - burned, burned * 0.05);
+    }
+
+    #[test]
+    fn realistic_burn_starts_after_turning() {
+        let mut app = TestApp::new();
+        app.world_mut().resource_mut::<LevelRules>().attitude = AttitudeControl::REALISTIC;
+
+        // the rocket faces up and has to turn a quarter to burn to the right
+        let rocket = app.spawn_rocket(Vec2::ZERO);
+        let capacity = app.fuel(rocket);
+
+        let rcs = app.world().get::<RcsTank>(rocket).unwrap();
+        let turn = AttitudeControl::REALISTIC
+            .turn_duration(Quat::IDENTITY, Vec2::X, Some(rcs))
+            .as_secs_f32();
+
+        app.thrust(rocket, Vec2::X, 1.0);
+        app.advance_secs(turn * 0.9);
+
+        assert_near(app.fuel(rocket), capacity, STEP);
+        assert_near(app.velocity(rocket).x, 0.0, 1.0);
+
+        // the full burn happens after the turn
+        app.advance_secs(turn * 0.1 + 0.5);
+
+        assert!(app.world().get::<Thrust>(rocket).is_some());
+        assert!(app.velocity(rocket).x > 0.0);
+
+        app.advance_secs(1.0);
+
+        assert_near(app.fuel(rocket), capacity - 1.0, 2.0 * STEP);
+        assert!(app.world().get::<Thrust>(rocket).is_none());
+    }
+
+    #[test]
+    fn realistic_burn_finishes_on_a_spinning_rocket() {
+        let mut app = TestApp::new();
+        app.world_mut().resource_mut::<LevelRules>().attitude = AttitudeControl::REALISTIC;
+
+        let rocket = app.spawn_rocket(Vec2::ZERO);
+        let capacity = app.fuel(rocket);
+
+        // spinning much faster than the rocket can turn, e.g. after a bounce
+        app.world_mut()
+            .entity_mut(rocket)
+            .insert(AngularVelocity(4.0 * PI));
+
+        app.thrust(rocket, Vec2::X, 1.0);
+        app.advance_secs(3.0);
+
+        assert!(app.world().get::<Thrust>(rocket).is_none());
+        assert_near(app.fuel(rocket), capacity - 1.0, 2.0 * STEP);
+        assert!(app.velocity(rocket).x > 0.0);
+    }
+
+    #[test]
+    fn jettison_switches_to_full_next_stage() {
+        let mut app = TestApp::new();
+        let rocket = app.spawn_rocket(Vec2::ZERO);
+
+        app.thrust(rocket, Vec2::Y, 1.0);
+        app.advance_secs(2.0);
+
+        app.world_mut().entity_mut(rocket).trigger(OnJettison);
+        app.step();
+
+        let stages = app.world().get::<Stages>(rocket).unwrap();
+        assert_eq!(stages.active_index(), 1);
+        assert!(!stages.can_jettison());
+
+        let next_stage_fuel = stages.active().fuel.as_secs();
+        assert_near(app.fuel(rocket), next_stage_fuel, STEP);
+
+        let debris = app
+            .world_mut()
+            .query_filtered::<(), With<Debris>>()
+            .iter(app.world())
+            .count();
+
+        assert_eq!(debris, 1);
     }
 }
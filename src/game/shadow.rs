@@ -1,20 +1,52 @@
+use crate::AppSystems;
+use crate::common::image_ops::generate_shadow_from_alpha;
 use crate::common::stopwatch::Stopwatch;
+use crate::game::light::LightSource;
+use crate::game::processed;
 use bevy::asset::RenderAssetUsages;
 use bevy::prelude::*;
 use bevy::sprite::Anchor;
-use image::{GrayAlphaImage, LumaA, Rgba, RgbaImage, imageops};
+use image::imageops;
 use std::collections::HashMap;
 
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<ShadowCache>();
-    app.add_observer(add_shadow_to_entity);
     app.add_observer(remove_shadow_from_entry);
+
+    app.add_systems(
+        Update,
+        (sync_shadows, update_shadow_offsets)
+            .chain()
+            .in_set(AppSystems::UpdateMarkers),
+    );
 }
 
+/// Draws a soft shadow below the sprite of this entity.
+///
+/// The shadow follows changes to the sprite, including its image,
+/// atlas index, anchor and flips.
 #[derive(Component, Reflect)]
 pub struct Shadow {
     pub offset_z: f32,
     pub sigma: f32,
+    pub color: Color,
+    /// How far the sprite hovers above the ground its shadow falls on.
+    /// Together with the [`LightSource`] this defines the offset and size of the shadow.
+    pub height: f32,
+    /// Additional offset of the shadow in world space.
+    pub offset: Vec2,
+}
+
+impl Default for Shadow {
+    fn default() -> Self {
+        Shadow {
+            offset_z: -0.01,
+            sigma: 3.0,
+            color: Color::BLACK,
+            height: 8.0,
+            offset: Vec2::ZERO,
+        }
+    }
 }
 
 #[derive(Component, Reflect)]
@@ -25,18 +57,22 @@ struct ShadowOf(Entity);
 #[relationship_target(relationship = ShadowOf)]
 struct Shadows(Vec<Entity>);
 
-#[derive(Resource, Default)]
-struct ShadowCache {
-    images: HashMap<Handle<Image>, Handle<Image>>,
+/// Marks a shadow caster whose shadow could not be created yet,
+/// e.g. because its image is still loading.
+#[derive(Component)]
+struct ShadowPending;
+
+/// Identifies the image a shadow was generated from.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash)]
+struct ShadowKey {
+    image: AssetId<Image>,
+    region: Option<URect>,
+    sigma: u32,
 }
 
-impl Default for Shadow {
-    fn default() -> Self {
-        Shadow {
-            offset_z: -0.01,
-            sigma: 3.0,
-        }
-    }
+#[derive(Resource, Default)]
+struct ShadowCache {
+    images: HashMap<ShadowKey, Handle<Image>>,
 }
 
 fn remove_shadow_from_entry(
@@ -54,108 +90,204 @@ fn remove_shadow_from_entry(
     }
 }
 
-fn add_shadow_to_entity(
-    trigger: Trigger<OnAdd, Shadow>,
-    query_sprite: Query<(&Sprite, &Shadow)>,
+fn sync_shadows(
     mut commands: Commands,
+    server: Res<AssetServer>,
+    layouts: Res<Assets<TextureAtlasLayout>>,
     mut images: ResMut<Assets<Image>>,
     mut cache: ResMut<ShadowCache>,
-) -> Result {
-    let (sprite, shadow_desc) = query_sprite.get(trigger.target())?;
+    casters: Query<
+        (Entity, &Sprite, &Shadow, Option<&Shadows>),
+        Or<(
+            Changed<Sprite>,
+            Changed<Shadow>,
+            Without<Shadows>,
+            With<ShadowPending>,
+        )>,
+    >,
+    mut shadows: Query<(&mut Sprite, &mut ShadowKey), Without<Shadow>>,
+) {
+    for (entity, sprite, shadow_desc, existing) in casters {
+        // the part of the image that is actually shown
+        let region = source_region(sprite, &layouts);
+        if sprite.texture_atlas.is_some() && region.is_none() {
+            // layout is not loaded yet
+            commands.entity(entity).insert(ShadowPending);
+            continue;
+        }
 
-    if sprite.anchor != Anchor::Center {
-        return Err("anchor must be center for Shadow".into());
-    }
+        let key = ShadowKey {
+            image: sprite.image.id(),
+            region,
+            sigma: shadow_desc.sigma.to_bits(),
+        };
 
-    let shadow = match cache
-        .images
-        .get(&sprite.image)
-        .and_then(|handle| images.get_strong_handle(handle.into()))
-    {
-        Some(handle) => handle,
-        None => {
-            // we could not get a strong handle to the cached shadow.
-            // need to create a new shadow from the image
-            let image = images.get(&sprite.image).ok_or("image not found")?;
-
-            let _watch = Stopwatch::new(format!(
-                "Creating shadow for size {}x{}",
-                image.width(),
-                image.height()
-            ));
-
-            // convert & create shadow
-            let image = image.clone().try_into_dynamic()?;
-            let shadow = generate_shadow_from_alpha(image.into_rgba8(), shadow_desc.sigma)?;
-
-            // create a bevy image from the shadow
-            let shadow = Image::from_dynamic(shadow.into(), true, RenderAssetUsages::RENDER_WORLD);
-
-            let handle = images.add(shadow);
-
-            // cache a weak clone of the handle
-            cache
-                .images
-                .insert(sprite.image.clone_weak(), handle.clone_weak());
-
-            handle
-        }
-    };
+        let existing = existing.and_then(|shadows| shadows.iter().next());
+
+        // only look up the shadow image if its source has changed
+        let image = match existing.and_then(|entity| shadows.get(entity).ok()) {
+            Some((shadow_sprite, current_key)) if *current_key == key => {
+                shadow_sprite.image.clone()
+            }
 
-    // spawn a new sprite as child
-    commands.entity(trigger.target()).with_child((
-        Name::new("Shadow"),
-        ShadowOf(trigger.target()),
-        Sprite {
-            image: shadow,
+            _ => {
+                let image =
+                    shadow_image(&server, &mut images, &mut cache, sprite, key, shadow_desc);
+
+                let Some(image) = image else {
+                    commands.entity(entity).insert(ShadowPending);
+                    continue;
+                };
+
+                image
+            }
+        };
+
+        commands.entity(entity).remove::<ShadowPending>();
+
+        // the shadow image is twice the size of the source, with the
+        // source in its center, so half the anchor lines both up
+        let shadow_sprite = Sprite {
+            image,
+            color: shadow_desc.color,
             custom_size: sprite.custom_size.map(|size| 2.0 * size),
-            anchor: Anchor::Center,
+            anchor: Anchor::Custom(sprite.anchor.as_vec() / 2.0),
+            flip_x: sprite.flip_x,
+            flip_y: sprite.flip_y,
             ..default()
-        },
-        Transform::from_xyz(0., 0., shadow_desc.offset_z),
-    ));
+        };
+
+        match existing.and_then(|entity| shadows.get_mut(entity).ok()) {
+            Some((mut sprite, mut current_key)) => {
+                *sprite = shadow_sprite;
+                *current_key = key;
+            }
 
-    Ok(())
+            None => {
+                // spawn a new sprite as child
+                commands.entity(entity).with_child((
+                    Name::new("Shadow"),
+                    ShadowOf(entity),
+                    key,
+                    shadow_sprite,
+                    Transform::from_xyz(0., 0., shadow_desc.offset_z),
+                ));
+            }
+        }
+    }
 }
 
-fn generate_shadow_from_alpha(image: RgbaImage, sigma: f32) -> Result<GrayAlphaImage> {
-    // start with a transparent black image
-    let mut mask = GrayAlphaImage::new(image.width() * 2, image.height() * 2);
+/// Moves and stretches the shadows away from the light. The offset is computed in
+/// world space, independent of the rotation and scale of the shadow caster.
+fn update_shadow_offsets(
+    light: Res<LightSource>,
+    casters: Query<(&Shadow, &GlobalTransform, &Shadows)>,
+    mut shadows: Query<&mut Transform, With<ShadowOf>>,
+) {
+    for (shadow_desc, transform, children) in casters {
+        let position = transform.translation().xy();
+        let (offset, scale) = light.project(position, shadow_desc.height);
+
+        let offset = transform
+            .affine()
+            .inverse()
+            .transform_vector3((offset + shadow_desc.offset).extend(0.0));
 
-    let offset_x = image.width() / 2;
-    let offset_y = image.height() / 2;
+        let translation = offset.xy().extend(shadow_desc.offset_z);
+        let scale = Vec3::new(scale, scale, 1.0);
 
-    // copy alpha channel into the center of the mask image
-    for (src, dst) in image.rows().zip(mask.rows_mut().skip(offset_y as usize)) {
-        for (Rgba(sp), LumaA(dp)) in src.zip(dst.skip(offset_x as usize)) {
-            dp[1] = sp[3];
+        for child in children.iter() {
+            let Ok(mut transform) = shadows.get_mut(child) else {
+                continue;
+            };
+
+            // do not trigger change detection if nothing has changed
+            if transform.translation != translation || transform.scale != scale {
+                transform.translation = translation;
+                transform.scale = scale;
+            }
         }
     }
+}
 
-    // increase the size of the mask by first applying a small blur
-    let mut mask = imageops::fast_blur(&mask, 1.0);
+/// Returns the rectangle of the image shown by the sprite,
+/// or `None` if the full image is shown.
+fn source_region(sprite: &Sprite, layouts: &Assets<TextureAtlasLayout>) -> Option<URect> {
+    let atlas_rect = sprite
+        .texture_atlas
+        .as_ref()
+        .and_then(|atlas| atlas.texture_rect(layouts));
 
-    // and then using a threshold
-    mask.pixels_mut()
-        .for_each(|LumaA([_, px])| *px = px.saturating_mul(5));
+    let rect = sprite.rect.map(|rect| rect.as_urect());
 
-    // now blur the mask to create the base shadow
-    let mut shadow = GrayAlphaImage::new(mask.width(), mask.height());
+    match (atlas_rect, rect) {
+        (Some(atlas_rect), Some(rect)) => Some(URect::from_corners(
+            atlas_rect.min + rect.min,
+            atlas_rect.min + rect.max,
+        )),
 
-    add(&mut shadow, &imageops::fast_blur(&mask, sigma), 2);
+        (atlas_rect, rect) => atlas_rect.or(rect),
+    }
+}
 
-    // reduce influence of the mask for pixels with wider shadow blur
-    add(&mut shadow, &imageops::fast_blur(&mask, sigma * 2.0), 3);
+/// Gets or creates the shadow image for the given sprite.
+/// Returns `None` if the source image is not yet loaded.
+fn shadow_image(
+    server: &AssetServer,
+    images: &mut Assets<Image>,
+    cache: &mut ShadowCache,
+    sprite: &Sprite,
+    key: ShadowKey,
+    shadow_desc: &Shadow,
+) -> Option<Handle<Image>> {
+    // prefer the shadow generated by `gravitate preprocess`
+    if key.region.is_none() {
+        let preprocessed = sprite
+            .image
+            .path()
+            .and_then(|path| processed::shadow(&path.path().to_string_lossy(), shadow_desc.sigma));
 
-    // reduce influence of the mask for pixels with wider shadow blur
-    add(&mut shadow, &imageops::fast_blur(&mask, sigma * 4.0), 4);
+        if let Some(path) = preprocessed {
+            return Some(server.load(path));
+        }
+    }
 
-    Ok(shadow)
-}
+    let cached = cache
+        .images
+        .get(&key)
+        .and_then(|handle| images.get_strong_handle(handle.id()));
+
+    if let Some(handle) = cached {
+        return Some(handle);
+    }
+
+    // we could not get a strong handle to the cached shadow.
+    // need to create a new shadow from the image
+    let image = images.get(&sprite.image)?;
+
+    let _watch = Stopwatch::new(format!(
+        "Creating shadow for size {}x{}",
+        image.width(),
+        image.height()
+    ));
+
+    // convert & create shadow
+    let mut image = image.clone().try_into_dynamic().ok()?.into_rgba8();
 
-#[inline]
-fn add(target: &mut GrayAlphaImage, source: &GrayAlphaImage, divider: u8) {
-    for (LumaA([_, tp]), LumaA([_, sp])) in target.pixels_mut().zip(source.pixels()) {
-        *tp = tp.saturating_add(*sp / divider);
+    if let Some(region) = key.region {
+        let size = region.size();
+        image = imageops::crop_imm(&image, region.min.x, region.min.y, size.x, size.y).to_image();
     }
+
+    let shadow = generate_shadow_from_alpha(&image, shadow_desc.sigma);
+
+    // create a bevy image from the shadow
+    let shadow = Image::from_dynamic(shadow.into(), true, RenderAssetUsages::RENDER_WORLD);
+
+    let handle = images.add(shadow);
+
+    // cache a weak clone of the handle
+    cache.images.insert(key, handle.clone_weak());
+
+    Some(handle)
 }
@@ -0,0 +1,732 @@
+//! Sprites with a shadow and a wiggle, drawn either on the CPU or on the GPU.
+//!
+//! The CPU path draws a [`Sprite`] with a [`Shadow`] child and a [`Wiggle`] that
+//! is updated every frame. The GPU path draws the sprite, its shadow and the wiggle
+//! in a single [`ShadowWiggleMaterial`], which keeps the entity count low and
+//! allows all sprites sharing an image to be batched. The shader has its own noise
+//! functions, which look like the noise types of `fastnoise_lite` without matching
+//! them exactly.
+//!
+//! Sprites are spawned for the CPU path. If the GPU path is selected, every new
+//! sprite with a shadow and a wiggle moves its [`Sprite`] into an [`EffectSprite`]
+//! and is drawn by the material instead. This covers stars, planet layers and
+//! rocket parts alike. Sprites keep the CPU path if they use something the material
+//! does not support, or if there is no shadow texture prepared by
+//! `gravitate preprocess` for their image.
+//!
+//! The wiggle of each sprite is seeded by [`Wiggle::seed`], which is passed to the
+//! shader as a [`MeshTag`], so it does not change while the sprite moves.
+
+use crate::AppSystems;
+use crate::game::light::LightSource;
+use crate::game::processed;
+use crate::game::shadow::Shadow;
+use crate::game::wiggle::{Wiggle, WiggleClock};
+use bevy::prelude::*;
+use bevy::render::mesh::MeshTag;
+use bevy::render::render_resource::{AsBindGroup, ShaderRef};
+use bevy::sprite::{AlphaMode2d, Anchor, Material2d, Material2dPlugin};
+use fastnoise_lite::NoiseType;
+use std::collections::HashMap;
+
+const SHADER_PATH: &str = "shaders/shadow_wiggle.wgsl";
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins(Material2dPlugin::<ShadowWiggleMaterial>::default());
+
+    app.init_resource::<SpriteEffects>();
+    app.init_resource::<EffectCache>();
+
+    app.add_observer(use_gpu_path);
+
+    app.add_systems(
+        Update,
+        (
+            sync_effect_sprites.in_set(AppSystems::UpdateMarkers),
+            update_material_light.run_if(resource_changed::<LightSource>),
+            update_material_time,
+        ),
+    );
+
+    #[cfg(feature = "dev")]
+    app.add_plugins(bench::plugin);
+}
+
+/// Selects how shadows and wiggles are drawn.
+#[derive(Resource, Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SpriteEffects {
+    /// A shadow child entity per sprite, wiggle computed on the CPU.
+    Cpu,
+    /// Shadow and wiggle computed in a shader on the sprite itself.
+    #[default]
+    Gpu,
+}
+
+#[derive(Asset, TypePath, AsBindGroup, Clone)]
+pub struct ShadowWiggleMaterial {
+    #[uniform(0)]
+    color: LinearRgba,
+    #[uniform(0)]
+    shadow_color: LinearRgba,
+    #[uniform(0)]
+    light: Vec4,
+    #[uniform(0)]
+    size: Vec2,
+    #[uniform(0)]
+    shadow_height: f32,
+    #[uniform(0)]
+    frequency: f32,
+    #[uniform(0)]
+    scale_rotation: f32,
+    #[uniform(0)]
+    scale_transform: f32,
+    /// Zero for a continuous wiggle.
+    #[uniform(0)]
+    steps_per_second: f32,
+    /// The noise function of the shader, see [`shader_noise`].
+    #[uniform(0)]
+    noise: u32,
+    /// Added to the time of the game, which the shader reads from the global
+    /// uniforms, to get the time of the clock the wiggle follows.
+    #[uniform(0)]
+    time_offset: f32,
+    clock: WiggleClock,
+    #[texture(1)]
+    #[sampler(2)]
+    image: Handle<Image>,
+    #[texture(3)]
+    #[sampler(4)]
+    shadow: Handle<Image>,
+}
+
+impl Material2d for ShadowWiggleMaterial {
+    fn vertex_shader() -> ShaderRef {
+        SHADER_PATH.into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        SHADER_PATH.into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode2d {
+        AlphaMode2d::Blend
+    }
+}
+
+/// Encodes the light for the shader.
+fn light_uniform(light: &LightSource) -> Vec4 {
+    match *light {
+        LightSource::Directional { direction } => direction.extend(0.0).extend(0.0),
+        LightSource::Point { position, height } => position.extend(height).extend(1.0),
+    }
+}
+
+/// Selects the noise function of the shader that looks most like the given noise.
+fn shader_noise(noise: NoiseType) -> u32 {
+    match noise {
+        NoiseType::Value | NoiseType::ValueCubic => 0,
+        NoiseType::OpenSimplex2 | NoiseType::OpenSimplex2S | NoiseType::Perlin => 1,
+        NoiseType::Cellular => 2,
+    }
+}
+
+/// Sprites share a material if they use the same image and size,
+/// cast the same shadow and wiggle the same way.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct MaterialKey {
+    image: AssetId<Image>,
+    /// Bits of width and height.
+    size: [u32; 2],
+    /// Bits of the linear sprite color.
+    color: [u32; 4],
+    /// Bits of the shadow height and the linear shadow color.
+    shadow: [u32; 5],
+    /// Bits of frequency, rotation scale, transform scale and steps per second.
+    wiggle: [u32; 4],
+    noise: u32,
+    clock: WiggleClock,
+}
+
+impl MaterialKey {
+    fn new(sprite: &Sprite, size: Vec2, shadow: &Shadow, wiggle: &Wiggle) -> Self {
+        let shadow_color = shadow.color.to_linear().to_f32_array();
+
+        Self {
+            image: sprite.image.id(),
+            size: size.to_array().map(f32::to_bits),
+            color: sprite.color.to_linear().to_f32_array().map(f32::to_bits),
+            shadow: [
+                shadow.height.to_bits(),
+                shadow_color[0].to_bits(),
+                shadow_color[1].to_bits(),
+                shadow_color[2].to_bits(),
+                shadow_color[3].to_bits(),
+            ],
+            wiggle: [
+                wiggle.frequency.to_bits(),
+                wiggle.scale_rotation.to_bits(),
+                wiggle.scale_transform.to_bits(),
+                wiggle.steps().unwrap_or_default().to_bits(),
+            ],
+            noise: shader_noise(wiggle.noise),
+            clock: wiggle.clock,
+        }
+    }
+}
+
+/// Meshes and materials shared between sprites.
+#[derive(Resource, Default)]
+struct EffectCache {
+    sprites: HashMap<MaterialKey, (Handle<Mesh>, Handle<ShadowWiggleMaterial>)>,
+}
+
+/// The sprite of an entity drawn on the GPU path. It takes the place of the
+/// [`Sprite`] component, so that the sprite is not drawn twice. Changes to it
+/// are picked up like changes to a [`Sprite`] would be.
+#[derive(Component, Clone)]
+pub struct EffectSprite(pub Sprite);
+
+/// The preprocessed shadow of the sprite, if the GPU path can draw it.
+/// The material supports neither atlases, sub rectangles, flips nor anchors
+/// other than the center.
+fn gpu_shadow(sprite: &Sprite, shadow: &Shadow) -> Option<&'static str> {
+    let supported = sprite.texture_atlas.is_none()
+        && sprite.rect.is_none()
+        && !sprite.flip_x
+        && !sprite.flip_y
+        && sprite.anchor == Anchor::Center
+        && shadow.offset == Vec2::ZERO;
+
+    if !supported {
+        return None;
+    }
+
+    let path = sprite.image.path()?.path().to_string_lossy().into_owned();
+    processed::shadow(&path, shadow.sigma)
+}
+
+/// Moves new sprites with a shadow and a wiggle to the GPU path, if it is selected.
+fn use_gpu_path(
+    trigger: Trigger<OnAdd, Shadow>,
+    mut commands: Commands,
+    mode: Res<SpriteEffects>,
+    sprites: Query<(&Sprite, &Shadow), With<Wiggle>>,
+) {
+    if *mode != SpriteEffects::Gpu {
+        return;
+    }
+
+    let Ok((sprite, shadow)) = sprites.get(trigger.target()) else {
+        return;
+    };
+
+    if gpu_shadow(sprite, shadow).is_none() {
+        return;
+    }
+
+    commands
+        .entity(trigger.target())
+        .remove::<Sprite>()
+        .insert(EffectSprite(sprite.clone()));
+}
+
+/// Keeps mesh, material and transform in sync with the sprite, its shadow and its
+/// wiggle. Sprites the GPU path can no longer draw are moved back to the CPU path.
+#[allow(clippy::type_complexity)]
+fn sync_effect_sprites(
+    mut commands: Commands,
+    server: Res<AssetServer>,
+    light: Res<LightSource>,
+    images: Res<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ShadowWiggleMaterial>>,
+    mut cache: ResMut<EffectCache>,
+    sprites: Query<
+        (Entity, &EffectSprite, &Shadow, &Wiggle, &mut Transform),
+        Or<(
+            Changed<EffectSprite>,
+            Changed<Shadow>,
+            Changed<Wiggle>,
+            Without<Mesh2d>,
+        )>,
+    >,
+) {
+    for (entity, EffectSprite(sprite), shadow_desc, wiggle, mut transform) in sprites {
+        let Some(shadow) = gpu_shadow(sprite, shadow_desc) else {
+            commands
+                .entity(entity)
+                .remove::<(
+                    EffectSprite,
+                    Mesh2d,
+                    MeshMaterial2d<ShadowWiggleMaterial>,
+                    MeshTag,
+                )>()
+                .insert(sprite.clone());
+
+            continue;
+        };
+
+        let size = sprite
+            .custom_size
+            .or_else(|| Some(images.get(&sprite.image)?.size_f32()));
+
+        let Some(size) = size else {
+            // image is not loaded yet
+            continue;
+        };
+
+        let key = MaterialKey::new(sprite, size, shadow_desc, wiggle);
+
+        let (mesh, material) = cache
+            .sprites
+            .entry(key)
+            .or_insert_with(|| {
+                let material = ShadowWiggleMaterial {
+                    color: sprite.color.to_linear(),
+                    shadow_color: shadow_desc.color.to_linear(),
+                    light: light_uniform(&light),
+                    size,
+                    shadow_height: shadow_desc.height,
+                    frequency: wiggle.frequency,
+                    scale_rotation: wiggle.scale_rotation,
+                    scale_transform: wiggle.scale_transform,
+                    steps_per_second: wiggle.steps().unwrap_or_default(),
+                    noise: key.noise,
+                    time_offset: 0.0,
+                    clock: wiggle.clock,
+                    image: sprite.image.clone(),
+                    shadow: server.load(shadow),
+                };
+
+                // the quad is twice the size of the sprite, to make room for the shadow
+                let mesh = meshes.add(Rectangle::from_size(2.0 * size));
+                (mesh, materials.add(material))
+            })
+            .clone();
+
+        // the wiggle offset and angle replace translation and rotation of the transform
+        transform.translation.x = wiggle.offset.x;
+        transform.translation.y = wiggle.offset.y;
+        transform.rotation = Quat::from_rotation_z(wiggle.offset_angle);
+
+        commands.entity(entity).insert((
+            Mesh2d(mesh),
+            MeshMaterial2d(material),
+            // the seed of the wiggle, which must not depend on the position of the sprite
+            MeshTag(wiggle.seed as u32),
+        ));
+    }
+}
+
+fn update_material_light(
+    light: Res<LightSource>,
+    mut materials: ResMut<Assets<ShadowWiggleMaterial>>,
+) {
+    let light = light_uniform(&light);
+
+    for (_, material) in materials.iter_mut() {
+        material.light = light;
+    }
+}
+
+/// Only wiggles that follow the real clock need an offset, which changes while
+/// the game is paused or slowed down. Other materials are left untouched, so
+/// they are not prepared and uploaded again every frame.
+fn update_material_time(
+    time: Res<Time<Virtual>>,
+    time_real: Res<Time<Real>>,
+    mut materials: ResMut<Assets<ShadowWiggleMaterial>>,
+) {
+    let real_offset = time_real
+        .elapsed()
+        .saturating_sub(time.elapsed())
+        .as_secs_f32();
+
+    let outdated: Vec<_> = materials
+        .iter()
+        .filter(|(_, material)| {
+            material.clock == WiggleClock::Real && material.time_offset != real_offset
+        })
+        .map(|(id, _)| id)
+        .collect();
+
+    for id in outdated {
+        if let Some(material) = materials.get_mut(id) {
+            material.time_offset = real_offset;
+        }
+    }
+}
+
+/// Compares the frame time of both paths. Press F8 to switch between them,
+/// the average frame time is logged every few seconds.
+#[cfg(feature = "dev")]
+mod bench {
+    use super::SpriteEffects;
+    use crate::game::starfield::StarChunk;
+    use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
+    use bevy::prelude::*;
+    use bevy::time::common_conditions::on_timer;
+    use std::time::Duration;
+
+    pub(super) fn plugin(app: &mut App) {
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin::default());
+        }
+
+        app.add_systems(
+            Update,
+            (
+                switch_sprite_effects,
+                log_frame_time.run_if(on_timer(Duration::from_secs(5))),
+            ),
+        );
+    }
+
+    fn switch_sprite_effects(
+        mut commands: Commands,
+        keys: Res<ButtonInput<KeyCode>>,
+        mut effects: ResMut<SpriteEffects>,
+        chunks: Query<Entity, With<StarChunk>>,
+    ) {
+        if !keys.just_pressed(KeyCode::F8) {
+            return;
+        }
+
+        *effects = match *effects {
+            SpriteEffects::Cpu => SpriteEffects::Gpu,
+            SpriteEffects::Gpu => SpriteEffects::Cpu,
+        };
+
+        info!("Switched sprite effects to {:?}", *effects);
+
+        // the starfield regenerates its chunks using the new path
+        for chunk in &chunks {
+            commands.entity(chunk).despawn();
+        }
+    }
+
+    fn log_frame_time(
+        diagnostics: Res<DiagnosticsStore>,
+        effects: Res<SpriteEffects>,
+        entities: Query<()>,
+    ) {
+        let Some(frame_time) = diagnostics
+            .get(&FrameTimeDiagnosticsPlugin::FRAME_TIME)
+            .and_then(|diagnostic| diagnostic.average())
+        else {
+            return;
+        };
+
+        info!(
+            "Sprite effects {:?}: {:.2}ms per frame with {} entities",
+            *effects,
+            frame_time,
+            entities.iter().count(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::rand::Rand;
+    use crate::game::{shadow, wiggle};
+    use bevy::asset::RenderAssetUsages;
+    use bevy::render::RenderApp;
+    use bevy::render::camera::RenderTarget;
+    use bevy::render::pipelined_rendering::PipelinedRenderingPlugin;
+    use bevy::render::render_resource::{
+        Extent3d, Maintain, TextureDimension, TextureFormat, TextureUsages,
+    };
+    use bevy::render::renderer::RenderDevice;
+    use bevy::time::TimeUpdateStrategy;
+    use bevy::window::ExitCondition;
+    use bevy::winit::WinitPlugin;
+    use std::time::{Duration, Instant};
+
+    const SPRITES: usize = 500;
+    const FRAMES: u32 = 300;
+
+    #[test]
+    fn only_real_clock_materials_follow_a_paused_game() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()));
+        app.init_asset::<Image>();
+        app.init_asset::<ShadowWiggleMaterial>();
+        app.add_systems(Update, update_material_time);
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            16,
+        )));
+
+        let virtual_clock = material(&mut app, Handle::default(), WiggleClock::Virtual);
+        let real_clock = material(&mut app, Handle::default(), WiggleClock::Real);
+
+        for _ in 0..4 {
+            app.update();
+        }
+
+        let offset = |app: &App, handle: &Handle<ShadowWiggleMaterial>| {
+            let materials = app.world().resource::<Assets<ShadowWiggleMaterial>>();
+            materials.get(handle).unwrap().time_offset
+        };
+
+        let before = offset(&app, &real_clock);
+
+        app.world_mut().resource_mut::<Time<Virtual>>().pause();
+        for _ in 0..4 {
+            app.update();
+        }
+
+        assert!(offset(&app, &real_clock) > before);
+        assert_eq!(offset(&app, &virtual_clock), 0.0);
+
+        // the virtual clock material was never touched after it was added
+        let events = app
+            .world()
+            .resource::<Events<AssetEvent<ShadowWiggleMaterial>>>();
+
+        let modified = events
+            .get_cursor()
+            .read(events)
+            .any(|event| event.is_modified(&virtual_clock));
+
+        assert!(!modified);
+    }
+
+    #[test]
+    fn moving_the_parent_keeps_the_wiggle_seed() {
+        let mut app = effects_app();
+        let image = star_image(&mut app);
+
+        let parent = app
+            .world_mut()
+            .spawn((Transform::default(), Visibility::Inherited))
+            .id();
+
+        let star = app
+            .world_mut()
+            .spawn((star(image, vec2(16.0, 8.0)), ChildOf(parent)))
+            .id();
+
+        app.update();
+
+        let state = |app: &App| {
+            let entity = app.world().entity(star);
+            (
+                entity.get::<MeshTag>().cloned(),
+                entity
+                    .get::<MeshMaterial2d<ShadowWiggleMaterial>>()
+                    .map(|material| material.id()),
+                *entity.get::<Transform>().unwrap(),
+            )
+        };
+
+        let before = state(&app);
+        let seed = app.world().get::<Wiggle>(star).unwrap().seed;
+
+        // the sprite moved to the GPU path, seeded by its wiggle
+        assert!(app.world().get::<Sprite>(star).is_none());
+        assert_eq!(before.0, Some(MeshTag(seed as u32)));
+        assert!(before.1.is_some());
+
+        // the starfield moves its layers with the camera every frame
+        for step in 1..=2 {
+            let mut transform = app.world_mut().get_mut::<Transform>(parent).unwrap();
+            transform.translation.x = 100.0 * step as f32;
+            app.update();
+
+            // tag, material and local transform are all the shader gets from the sprite
+            assert_eq!(state(&app), before);
+        }
+    }
+
+    #[test]
+    fn unsupported_sprites_stay_on_the_cpu_path() {
+        let mut app = effects_app();
+
+        let mut sprite = star(star_image(&mut app), Vec2::ZERO);
+        sprite.2.flip_x = true;
+
+        let star = app.world_mut().spawn(sprite).id();
+
+        app.update();
+
+        assert!(app.world().get::<Sprite>(star).is_some());
+        assert!(app.world().get::<EffectSprite>(star).is_none());
+        assert!(app.world().get::<Mesh2d>(star).is_none());
+    }
+
+    /// Measures the frame time of wiggling sprites with a shadow on each path. The
+    /// sprites are rendered into an image and every frame waits for the GPU to finish.
+    /// Needs a graphics adapter, run it in release mode:
+    /// `cargo test --release compare_frame_time -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn compare_frame_time() {
+        for mode in [SpriteEffects::Cpu, SpriteEffects::Gpu] {
+            let mut app = render_app();
+            spawn_sprites(&mut app, mode);
+
+            // let the shadows, shaders and pipelines settle
+            for _ in 0..120 {
+                update_and_wait(&mut app);
+            }
+
+            let start = Instant::now();
+            for _ in 0..FRAMES {
+                update_and_wait(&mut app);
+            }
+
+            let entities = app.world_mut().query::<()>().iter(app.world()).count();
+
+            println!(
+                "{mode:?}: {:.3}ms per frame for {SPRITES} sprites, {entities} entities",
+                start.elapsed().as_secs_f64() * 1000.0 / FRAMES as f64,
+            );
+        }
+    }
+
+    /// Moves sprites to the GPU path, without rendering them.
+    fn effects_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), TransformPlugin));
+
+        app.init_asset::<Image>();
+        app.init_asset::<Mesh>();
+        app.init_asset::<ShadowWiggleMaterial>();
+
+        app.insert_resource(Rand::seeded(0));
+        app.insert_resource(LightSource::default());
+        app.insert_resource(SpriteEffects::Gpu);
+        app.init_resource::<EffectCache>();
+
+        app.add_plugins(wiggle::plugin);
+        app.add_observer(use_gpu_path);
+        app.add_systems(Update, sync_effect_sprites);
+
+        crate::configure_app_systems(&mut app);
+
+        app
+    }
+
+    fn update_and_wait(app: &mut App) {
+        app.update();
+
+        let device = app.sub_app(RenderApp).world().resource::<RenderDevice>();
+        device.poll(Maintain::Wait);
+    }
+
+    /// The rendering part of the game, drawing into an image instead of a window.
+    fn render_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(
+            DefaultPlugins
+                .set(WindowPlugin {
+                    primary_window: None,
+                    exit_condition: ExitCondition::DontExit,
+                    close_when_requested: false,
+                })
+                .disable::<WinitPlugin>()
+                // render each frame before the next one starts
+                .disable::<PipelinedRenderingPlugin>(),
+        );
+
+        app.insert_resource(Rand::seeded(0));
+        app.insert_resource(LightSource::default());
+        app.add_plugins((wiggle::plugin, shadow::plugin, super::plugin));
+
+        crate::configure_app_systems(&mut app);
+
+        app.finish();
+        app.cleanup();
+
+        let mut target = Image::new_fill(
+            Extent3d {
+                width: 1024,
+                height: 1536,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[0, 0, 0, 255],
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        );
+
+        target.texture_descriptor.usage |= TextureUsages::RENDER_ATTACHMENT;
+
+        let target = app.world_mut().resource_mut::<Assets<Image>>().add(target);
+
+        app.world_mut().spawn((
+            Camera2d,
+            Camera {
+                target: RenderTarget::Image(target.into()),
+                ..default()
+            },
+        ));
+
+        app
+    }
+
+    fn material(
+        app: &mut App,
+        image: Handle<Image>,
+        clock: WiggleClock,
+    ) -> Handle<ShadowWiggleMaterial> {
+        let wiggle = Wiggle::default();
+
+        app.world_mut()
+            .resource_mut::<Assets<ShadowWiggleMaterial>>()
+            .add(ShadowWiggleMaterial {
+                color: LinearRgba::WHITE,
+                shadow_color: LinearRgba::BLACK,
+                light: Vec4::ZERO,
+                size: Vec2::splat(64.0),
+                shadow_height: 8.0,
+                frequency: wiggle.frequency,
+                scale_rotation: wiggle.scale_rotation,
+                scale_transform: wiggle.scale_transform,
+                steps_per_second: 0.0,
+                noise: shader_noise(wiggle.noise),
+                time_offset: 0.0,
+                clock,
+                shadow: image.clone(),
+                image,
+            })
+    }
+
+    fn spawn_sprites(app: &mut App, mode: SpriteEffects) {
+        app.insert_resource(mode);
+
+        let image = star_image(app);
+
+        for idx in 0..SPRITES {
+            let offset = vec2((idx % 25) as f32, (idx / 25) as f32) * 40.0 - vec2(480.0, 400.0);
+
+            app.world_mut().spawn(star(image.clone(), offset));
+        }
+    }
+
+    /// An image with a preprocessed shadow. It does not need to be loaded,
+    /// as the stars have a custom size.
+    fn star_image(app: &mut App) -> Handle<Image> {
+        app.world()
+            .resource::<AssetServer>()
+            .load("images/star-small.png")
+    }
+
+    fn star(image: Handle<Image>, offset: Vec2) -> (Shadow, Wiggle, Sprite) {
+        (
+            Shadow::default(),
+            Wiggle {
+                offset,
+                clock: WiggleClock::Real,
+                ..default()
+            },
+            Sprite {
+                image,
+                custom_size: Some(Vec2::splat(64.0)),
+                ..default()
+            },
+        )
+    }
+}
@@ -0,0 +1,228 @@
+//! An endless parallax starfield.
+//!
+//! The stars are split into layers at different depths. Each layer is divided
+//! into square chunks that are generated when they come into view and removed
+//! once they are far away again. Chunks are seeded by their coordinate, so
+//! coming back to a place shows the same stars.
+
+use crate::common::rand::{Generate, Rand};
+use crate::game::cv::LAYER_STARS;
+use crate::game::shadow::Shadow;
+use crate::game::wiggle::{Wiggle, WiggleClock};
+use crate::screens::Screen;
+use crate::{AppSystems, MainCamera, game};
+use bevy::platform::hash::FixedHasher;
+use bevy::prelude::*;
+use rand::Rng;
+use rand::prelude::IndexedRandom;
+use std::f32::consts::PI;
+use std::hash::BuildHasher;
+
+/// Size of a chunk in layer space.
+const CHUNK_SIZE: f32 = 1024.0;
+
+/// Chunks further away from the visible area than this many chunks are removed.
+const CHUNK_KEEP_DISTANCE: i32 = 2;
+
+struct StarLayerDef {
+    /// How much the layer moves with the world, one moves like the world itself.
+    depth: f32,
+    stars_per_chunk: usize,
+    clearance: f32,
+    scale: f32,
+}
+
+const STAR_LAYERS: &[StarLayerDef] = &[
+    StarLayerDef {
+        depth: 0.2,
+        stars_per_chunk: 8,
+        clearance: 192.0,
+        scale: 0.4,
+    },
+    StarLayerDef {
+        depth: 0.45,
+        stars_per_chunk: 6,
+        clearance: 256.0,
+        scale: 0.65,
+    },
+    StarLayerDef {
+        depth: 0.75,
+        stars_per_chunk: 4,
+        clearance: 256.0,
+        scale: 1.0,
+    },
+];
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_star_layers);
+
+    app.add_systems(
+        Update,
+        (move_star_layers, update_star_chunks)
+            .chain()
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::UpdateMarkers),
+    );
+}
+
+#[derive(Component)]
+struct StarLayer {
+    index: usize,
+    depth: f32,
+}
+
+#[derive(Component)]
+pub struct StarChunk {
+    coord: IVec2,
+}
+
+fn spawn_star_layers(mut commands: Commands) {
+    for (index, def) in STAR_LAYERS.iter().enumerate() {
+        commands.spawn((
+            Name::new("StarLayer"),
+            StateScoped(Screen::Gameplay),
+            StarLayer {
+                index,
+                depth: def.depth,
+            },
+            // deeper layers are drawn further back
+            LAYER_STARS.offset_by(index as i32 - STAR_LAYERS.len() as i32),
+            Visibility::Inherited,
+        ));
+    }
+}
+
+/// Offsets the layers, so they appear to move slower than the world.
+fn move_star_layers(
+    camera: Single<&Transform, With<MainCamera>>,
+    layers: Query<(&StarLayer, &mut Transform), Without<MainCamera>>,
+) {
+    let camera = camera.translation.xy();
+
+    for (layer, mut transform) in layers {
+        let offset = camera * (1.0 - layer.depth);
+        transform.translation.x = offset.x;
+        transform.translation.y = offset.y;
+    }
+}
+
+fn update_star_chunks(
+    mut commands: Commands,
+    assets: Res<game::Assets>,
+    camera: Single<(&Transform, &Projection), With<MainCamera>>,
+    layers: Query<(Entity, &StarLayer, Option<&Children>)>,
+    chunks: Query<&StarChunk>,
+) {
+    let (camera_transform, projection) = camera.into_inner();
+
+    let Projection::Orthographic(projection) = projection else {
+        return;
+    };
+
+    for (layer_entity, layer, children) in layers {
+        // the part of the layer that is currently visible
+        let visible = Rect::from_center_size(
+            camera_transform.translation.xy() * layer.depth + projection.area.center(),
+            projection.area.size(),
+        );
+
+        let min = (visible.min / CHUNK_SIZE).floor().as_ivec2();
+        let max = (visible.max / CHUNK_SIZE).floor().as_ivec2();
+
+        let mut existing = Vec::new();
+
+        for child in children.into_iter().flatten() {
+            let Ok(chunk) = chunks.get(*child) else {
+                continue;
+            };
+
+            let keep = chunk.coord.cmpge(min - CHUNK_KEEP_DISTANCE).all()
+                && chunk.coord.cmple(max + CHUNK_KEEP_DISTANCE).all();
+
+            if keep {
+                existing.push(chunk.coord);
+            } else {
+                commands.entity(*child).despawn();
+            }
+        }
+
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let coord = ivec2(x, y);
+                if existing.contains(&coord) {
+                    continue;
+                }
+
+                let chunk = spawn_chunk(&mut commands, &assets, layer.index, coord);
+                commands.entity(layer_entity).add_child(chunk);
+            }
+        }
+    }
+}
+
+fn spawn_chunk(
+    commands: &mut Commands,
+    assets: &game::Assets,
+    layer: usize,
+    coord: IVec2,
+) -> Entity {
+    let def = &STAR_LAYERS[layer];
+
+    // the same chunk always gets the same stars
+    let packed = (coord.x as u32 as u64) << 32 | coord.y as u32 as u64;
+    let seed = FixedHasher.hash_one((layer, packed));
+    let mut rand = Rand::seeded(seed);
+
+    let half = CHUNK_SIZE / 2.0;
+    let chunk = Rect::from_center_half_size(Vec2::ZERO, Vec2::splat(half));
+
+    // generate within the circle around the chunk and keep the points within the square
+    let mut g = Generate::new(half * 1.5, 0.0, Vec2::ZERO);
+    let points = g.generate(
+        |radius| rand.vec2() * radius,
+        def.stars_per_chunk,
+        def.clearance,
+    );
+
+    let stars: Vec<_> = points
+        .into_iter()
+        .filter(|point| chunk.contains(*point))
+        .map(|point| {
+            let rotation = rand.random_range(0. ..2.0 * PI);
+            let image = [&assets.star_small, &assets.star_large]
+                .choose(&mut rand)
+                .map(|image| Handle::clone(image))
+                .unwrap_or_default();
+
+            let wiggle = Wiggle {
+                offset: point.floor(),
+                offset_angle: rotation,
+                // the background keeps its hand drawn jitter while the game is paused
+                clock: WiggleClock::Real,
+                ..default()
+            };
+
+            (image, wiggle)
+        })
+        .collect();
+
+    commands
+        .spawn((
+            Name::new("StarChunk"),
+            StarChunk { coord },
+            Transform::from_translation(((coord.as_vec2() + 0.5) * CHUNK_SIZE).extend(0.0)),
+            Visibility::Inherited,
+        ))
+        .with_children(|parent| {
+            for (image, wiggle) in stars {
+                parent.spawn((
+                    Name::new("Star"),
+                    Shadow::default(),
+                    wiggle,
+                    Transform::from_scale(Vec3::splat(def.scale)),
+                    Sprite::from_image(image),
+                ));
+            }
+        })
+        .id()
+}
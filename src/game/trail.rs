@@ -0,0 +1,99 @@
+//! Records the path the player actually flew and draws it as a fading line.
+
+use crate::AppSystems;
+use crate::common::pause::PausableSystems;
+use crate::game::cv::{COLOR_TRAIL_BURN, COLOR_TRAIL_COAST, LAYER_TRAIL};
+use crate::game::player::Thrust;
+use crate::screens::Screen;
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+/// Minimum distance between two recorded points.
+const TRAIL_SPACING: f32 = 24.0;
+
+/// Maximum number of segments before the oldest ones are removed.
+const TRAIL_MAX_SEGMENTS: usize = 600;
+
+const TRAIL_THICKNESS: f32 = 4.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        record_trail
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+}
+
+/// Records the position of its entity over time.
+#[derive(Component, Default)]
+pub struct Trail {
+    last: Option<Vec2>,
+    segments: VecDeque<Entity>,
+}
+
+#[derive(Component)]
+struct TrailSegment {
+    color: Color,
+}
+
+fn record_trail(
+    mut commands: Commands,
+    trails: Query<(&mut Trail, &Transform, Has<Thrust>)>,
+    mut segments: Query<(&TrailSegment, &mut Sprite)>,
+) {
+    for (mut trail, transform, burning) in trails {
+        let position = transform.translation.xy();
+
+        let Some(last) = trail.last else {
+            trail.last = Some(position);
+            continue;
+        };
+
+        let delta = position - last;
+        if delta.length() < TRAIL_SPACING {
+            continue;
+        }
+
+        let color = if burning {
+            COLOR_TRAIL_BURN
+        } else {
+            COLOR_TRAIL_COAST
+        };
+
+        let segment = commands
+            .spawn((
+                Name::new("TrailSegment"),
+                StateScoped(Screen::Gameplay),
+                TrailSegment { color },
+                LAYER_TRAIL,
+                Transform::from_translation(last.midpoint(position).extend(0.0))
+                    .with_rotation(Quat::from_rotation_z(delta.to_angle())),
+                Sprite::from_color(color, vec2(delta.length(), TRAIL_THICKNESS)),
+            ))
+            .id();
+
+        trail.last = Some(position);
+        trail.segments.push_back(segment);
+
+        while trail.segments.len() > TRAIL_MAX_SEGMENTS {
+            if let Some(oldest) = trail.segments.pop_front() {
+                commands.entity(oldest).despawn();
+            }
+        }
+
+        // older segments fade out
+        let count = trail.segments.len() as f32;
+        for (idx, entity) in trail.segments.iter().enumerate() {
+            let Ok((segment, mut sprite)) = segments.get_mut(*entity) else {
+                continue;
+            };
+
+            let fade = (idx + 1) as f32 / count;
+            sprite.color = segment
+                .color
+                .with_alpha(segment.color.alpha() * fade.sqrt());
+        }
+    }
+}
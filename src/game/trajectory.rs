@@ -0,0 +1,129 @@
+//! Predicts the path of the player's rocket using the attraction model.
+//!
+//! The path is only drawn on the minimap and the system map,
+//! which have their own dots on the map render layer.
+
+use crate::AppSystems;
+use crate::game::attraction::{Attractor, attraction_force};
+use crate::game::planet::Planet;
+use crate::game::player::{Player, Thrust};
+use crate::screens::Screen;
+use avian2d::prelude::{ComputedMass, LinearVelocity};
+use bevy::prelude::*;
+
+/// Number of simulation steps to look ahead.
+const PREDICTION_STEPS: usize = 256;
+
+/// Duration of a single simulation step in seconds.
+const PREDICTION_STEP: f32 = 1.0 / 32.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        update_predicted_path
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::Update),
+    );
+}
+
+/// The positions the rocket will pass through if nothing changes.
+#[derive(Component, Default)]
+pub struct PredictedPath {
+    pub points: Vec<Vec2>,
+}
+
+/// A snapshot of an attractor used during prediction.
+#[derive(Clone, Copy, Debug)]
+pub struct Body {
+    pub position: Vec2,
+    pub mass: f32,
+    pub radius: f32,
+}
+
+/// A rocket to simulate.
+pub struct Ballistic {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub mass: f32,
+    /// Force that is applied for the given number of seconds.
+    pub thrust: Option<(Vec2, f32)>,
+}
+
+/// Simulates the rocket for the given number of steps.
+/// Stops early if the rocket hits the surface of one of the bodies.
+pub fn predict(mut rocket: Ballistic, bodies: &[Body], steps: usize, dt: f32) -> Vec<Vec2> {
+    let mut points = Vec::with_capacity(steps);
+
+    for _ in 0..steps {
+        let mut force = Vec2::ZERO;
+
+        for body in bodies {
+            force += attraction_force(body.position, body.mass, rocket.position);
+        }
+
+        if let Some((thrust, remaining)) = rocket.thrust.as_mut() {
+            force += *thrust;
+
+            *remaining -= dt;
+            if *remaining <= 0.0 {
+                rocket.thrust = None;
+            }
+        }
+
+        // semi implicit euler, just like the physics engine
+        rocket.velocity += force / rocket.mass * dt;
+        rocket.position += rocket.velocity * dt;
+
+        let crashed = bodies
+            .iter()
+            .any(|body| body.position.distance(rocket.position) < body.radius);
+
+        if crashed {
+            break;
+        }
+
+        points.push(rocket.position);
+    }
+
+    points
+}
+
+/// Collects the attractors of the current level for prediction.
+pub fn collect_bodies<'a>(
+    attractors: impl IntoIterator<Item = (&'a Transform, &'a ComputedMass, &'a Planet)>,
+) -> Vec<Body> {
+    attractors
+        .into_iter()
+        .map(|(transform, mass, planet)| Body {
+            position: transform.translation.xy(),
+            mass: mass.value(),
+            radius: planet.radius,
+        })
+        .collect()
+}
+
+fn update_predicted_path(
+    player: Single<
+        (
+            &Transform,
+            &LinearVelocity,
+            &ComputedMass,
+            Option<&Thrust>,
+            &mut PredictedPath,
+        ),
+        With<Player>,
+    >,
+    attractors: Query<(&Transform, &ComputedMass, &Planet), With<Attractor>>,
+) {
+    let (transform, velocity, mass, thrust, mut path) = player.into_inner();
+
+    let rocket = Ballistic {
+        position: transform.translation.xy(),
+        velocity: velocity.0,
+        mass: mass.value(),
+        thrust: thrust.map(|thrust| (thrust.force, thrust.remaining.as_secs_f32())),
+    };
+
+    let bodies = collect_bodies(attractors);
+    path.points = predict(rocket, &bodies, PREDICTION_STEPS, PREDICTION_STEP);
+}
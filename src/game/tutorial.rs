@@ -0,0 +1,379 @@
+//! A scripted tutorial that walks new players through the controls.
+//!
+//! A tutorial is a list of [`TutorialStep`]s, loaded from a `.tutorial.ron`
+//! file in `assets/tutorial`. Each step shows a prompt and optionally a hint
+//! pointing at something in the world. The step stays active until the game
+//! event described by its [`TutorialTrigger`] happens.
+
+use crate::asset_tracking::LoadResource;
+use crate::game::input::{InputActive, OnThurst};
+use crate::game::landing::Landed;
+use crate::game::player::Player;
+use crate::screens::Screen;
+use crate::ui::widget;
+use crate::{AppSystems, MainCamera};
+use avian2d::prelude::OnCollisionStart;
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use bevy::ui::Val::*;
+use serde::Deserialize;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<TutorialScript>();
+    app.init_asset_loader::<TutorialScriptLoader>();
+    app.load_resource::<TutorialAssets>();
+
+    app.add_observer(advance_on_input);
+    app.add_observer(advance_on_thrust);
+    app.add_observer(advance_on_landing);
+    app.add_observer(advance_on_collision);
+
+    app.add_systems(
+        Update,
+        (
+            advance_on_zone,
+            spawn_tutorial_prompt.run_if(resource_exists_and_changed::<Tutorial>),
+            position_tutorial_hint,
+        )
+            .chain()
+            .run_if(in_state(Screen::Gameplay).and(resource_exists::<Tutorial>))
+            .in_set(AppSystems::Update),
+    );
+}
+
+/// The tutorial of the tutorial level.
+pub const TUTORIAL_PATH: &str = "tutorial/basics.tutorial.ron";
+
+/// A tutorial as stored in a `.tutorial.ron` file.
+#[derive(Asset, TypePath, Deserialize, Clone, Debug)]
+pub struct TutorialScript {
+    pub steps: Vec<TutorialStep>,
+}
+
+/// A single step of a tutorial.
+#[derive(Deserialize, Clone, Debug)]
+pub struct TutorialStep {
+    pub prompt: String,
+    #[serde(default)]
+    pub hint: Option<Hint>,
+    /// The event that completes this step, `None` for the final step.
+    #[serde(default)]
+    pub advance_on: Option<TutorialTrigger>,
+}
+
+/// A label shown next to something in the world.
+#[derive(Deserialize, Clone, Debug)]
+pub struct Hint {
+    pub text: String,
+    pub target: HintTarget,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub enum HintTarget {
+    Player,
+    Position([f32; 2]),
+}
+
+/// Game events that can complete a tutorial step.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub enum TutorialTrigger {
+    /// The player started aiming.
+    InputStarted,
+    /// The player released the input and the engine fired.
+    Thrust,
+    /// The rocket touched down softly on a planet.
+    Landed,
+    /// The rocket bumped into something.
+    Collision,
+    /// The rocket entered the given circle.
+    ReachZone { center: [f32; 2], radius: f32 },
+}
+
+/// The tutorial that is currently running.
+#[derive(Resource)]
+pub struct Tutorial {
+    steps: Vec<TutorialStep>,
+    current: usize,
+}
+
+impl Tutorial {
+    pub fn new(steps: Vec<TutorialStep>) -> Self {
+        Self { steps, current: 0 }
+    }
+
+    pub fn step(&self) -> Option<&TutorialStep> {
+        self.steps.get(self.current)
+    }
+
+    fn advance(&mut self) {
+        self.current += 1;
+        info!("Tutorial advanced to step {}", self.current);
+    }
+
+    fn advances_on(&self, trigger: &TutorialTrigger) -> bool {
+        self.step().and_then(|s| s.advance_on.as_ref()) == Some(trigger)
+    }
+}
+
+/// The tutorials of the game, loaded before the game starts.
+#[derive(Resource, Asset, TypePath, Clone)]
+pub struct TutorialAssets {
+    #[dependency]
+    pub basics: Handle<TutorialScript>,
+}
+
+impl FromWorld for TutorialAssets {
+    fn from_world(world: &mut World) -> Self {
+        let server = world.resource::<AssetServer>();
+
+        Self {
+            basics: server.load(TUTORIAL_PATH),
+        }
+    }
+}
+
+#[derive(Default, TypePath)]
+struct TutorialScriptLoader;
+
+impl AssetLoader for TutorialScriptLoader {
+    type Asset = TutorialScript;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<TutorialScript, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tutorial.ron"]
+    }
+}
+
+#[derive(Component)]
+struct TutorialPrompt;
+
+#[derive(Component)]
+struct TutorialHint;
+
+fn advance_on_input(_: Trigger<OnAdd, InputActive>, tutorial: Option<ResMut<Tutorial>>) {
+    let Some(mut tutorial) = tutorial else {
+        return;
+    };
+
+    if tutorial.advances_on(&TutorialTrigger::InputStarted) {
+        tutorial.advance();
+    }
+}
+
+fn advance_on_thrust(_: Trigger<OnThurst>, tutorial: Option<ResMut<Tutorial>>) {
+    let Some(mut tutorial) = tutorial else {
+        return;
+    };
+
+    if tutorial.advances_on(&TutorialTrigger::Thrust) {
+        tutorial.advance();
+    }
+}
+
+fn advance_on_landing(
+    trigger: Trigger<OnAdd, Landed>,
+    tutorial: Option<ResMut<Tutorial>>,
+    players: Query<(), With<Player>>,
+) {
+    let Some(mut tutorial) = tutorial else {
+        return;
+    };
+
+    if players.contains(trigger.target()) && tutorial.advances_on(&TutorialTrigger::Landed) {
+        tutorial.advance();
+    }
+}
+
+fn advance_on_collision(
+    trigger: Trigger<OnCollisionStart>,
+    tutorial: Option<ResMut<Tutorial>>,
+    players: Query<(), With<Player>>,
+) {
+    let Some(mut tutorial) = tutorial else {
+        return;
+    };
+
+    if players.contains(trigger.target()) && tutorial.advances_on(&TutorialTrigger::Collision) {
+        tutorial.advance();
+    }
+}
+
+fn advance_on_zone(mut tutorial: ResMut<Tutorial>, player: Single<&Transform, With<Player>>) {
+    let Some(TutorialTrigger::ReachZone { center, radius }) =
+        tutorial.step().and_then(|s| s.advance_on.as_ref())
+    else {
+        return;
+    };
+
+    if player.translation.xy().distance(Vec2::from(*center)) <= *radius {
+        tutorial.advance();
+    }
+}
+
+fn spawn_tutorial_prompt(
+    mut commands: Commands,
+    tutorial: Res<Tutorial>,
+    existing: Query<Entity, Or<(With<TutorialPrompt>, With<TutorialHint>)>>,
+) {
+    for entity in &existing {
+        commands.entity(entity).despawn();
+    }
+
+    let Some(step) = tutorial.step() else {
+        return;
+    };
+
+    commands.spawn((
+        Name::new("Tutorial Prompt"),
+        StateScoped(Screen::Gameplay),
+        TutorialPrompt,
+        Node {
+            position_type: PositionType::Absolute,
+            width: Percent(100.0),
+            bottom: Px(40.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        Pickable::IGNORE,
+        children![(
+            widget::label(step.prompt.as_str()),
+            TextLayout::new_with_justify(JustifyText::Center),
+        )],
+    ));
+
+    if let Some(hint) = &step.hint {
+        commands.spawn((
+            Name::new("Tutorial Hint"),
+            StateScoped(Screen::Gameplay),
+            TutorialHint,
+            Node {
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            Visibility::Hidden,
+            Pickable::IGNORE,
+            children![widget::label(hint.text.as_str())],
+        ));
+    }
+}
+
+fn position_tutorial_hint(
+    tutorial: Res<Tutorial>,
+    camera: Single<(&Camera, &GlobalTransform), With<MainCamera>>,
+    player: Single<&Transform, With<Player>>,
+    hints: Query<(&mut Node, &mut Visibility), With<TutorialHint>>,
+) {
+    let Some(hint) = tutorial.step().and_then(|step| step.hint.as_ref()) else {
+        return;
+    };
+
+    let target = match hint.target {
+        HintTarget::Player => player.translation.xy(),
+        HintTarget::Position(position) => Vec2::from(position),
+    };
+
+    let (camera, camera_transform) = *camera;
+
+    for (mut node, mut visibility) in hints {
+        let Ok(viewport) = camera.world_to_viewport(camera_transform, target.extend(0.0)) else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+
+        // show the hint a little to the right of the target
+        node.left = Px(viewport.x + 48.0);
+        node.top = Px(viewport.y - 12.0);
+        visibility.set_if_neq(Visibility::Inherited);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestApp;
+    use avian2d::prelude::{Collider, RigidBody};
+
+    fn step(advance_on: Option<TutorialTrigger>) -> TutorialStep {
+        TutorialStep {
+            prompt: String::new(),
+            hint: None,
+            advance_on,
+        }
+    }
+
+    #[test]
+    fn checked_in_tutorial_ends_with_final_step() {
+        let source = include_str!("../../assets/tutorial/basics.tutorial.ron");
+        let script: TutorialScript = ron::from_str(source).unwrap();
+
+        let (last, steps) = script.steps.split_last().unwrap();
+        assert!(steps.iter().all(|step| step.advance_on.is_some()));
+        assert!(last.advance_on.is_none());
+    }
+
+    #[test]
+    fn landing_advances_tutorial() {
+        let mut app = TestApp::new().with_plugins(|app: &mut App| {
+            app.add_observer(advance_on_landing);
+        });
+
+        app.world_mut().insert_resource(Tutorial::new(vec![
+            step(Some(TutorialTrigger::Landed)),
+            step(None),
+        ]));
+
+        let rocket = app.spawn_rocket(Vec2::ZERO);
+        app.step();
+        assert_eq!(app.world().resource::<Tutorial>().current, 0);
+
+        app.world_mut().entity_mut(rocket).insert(Landed {
+            planet: Entity::PLACEHOLDER,
+            normal: Vec2::Y,
+            clearance: 0.0,
+        });
+
+        assert_eq!(app.world().resource::<Tutorial>().current, 1);
+    }
+
+    #[test]
+    fn collision_advances_tutorial() {
+        let mut app = TestApp::new().with_plugins(|app: &mut App| {
+            app.add_observer(advance_on_collision);
+        });
+
+        app.world_mut().insert_resource(Tutorial::new(vec![
+            step(Some(TutorialTrigger::Collision)),
+            step(None),
+        ]));
+
+        app.spawn_rocket(Vec2::ZERO);
+        app.step();
+        assert_eq!(app.world().resource::<Tutorial>().current, 0);
+
+        app.world_mut().spawn((
+            Transform::default(),
+            RigidBody::Static,
+            Collider::circle(64.0),
+        ));
+
+        for _ in 0..4 {
+            app.step();
+        }
+
+        assert_eq!(app.world().resource::<Tutorial>().current, 1);
+    }
+}
@@ -1,7 +1,8 @@
 use crate::AppSystems;
 use crate::common::rand::Rand;
+use crate::game::sprite_effects::EffectSprite;
 use bevy::prelude::*;
-use fastnoise_lite::FastNoiseLite;
+use fastnoise_lite::{FastNoiseLite, NoiseType};
 use rand::Rng;
 
 pub(super) fn plugin(app: &mut App) {
@@ -12,15 +13,35 @@ pub(super) fn plugin(app: &mut App) {
 }
 
 #[derive(Component)]
-#[require(Transform)]
+#[require(Transform, WiggleNoise)]
 pub struct Wiggle {
     pub seed: i32,
     pub scale_rotation: f32,
     pub scale_transform: f32,
     pub offset: Vec2,
     pub offset_angle: f32,
+    pub frequency: f32,
+    pub noise: NoiseType,
+    pub clock: WiggleClock,
+    /// Only update the wiggle this many times per second, for a stop-motion look.
+    /// Zero or less wiggles continuously, like `None`, see [`Wiggle::steps`].
+    pub steps_per_second: Option<f32>,
 }
 
+/// The clock that drives a [`Wiggle`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum WiggleClock {
+    /// Follows the game, slows down with it and stops while paused.
+    #[default]
+    Virtual,
+    /// Keeps wiggling while the game is paused or slowed down.
+    Real,
+}
+
+/// The noise of a [`Wiggle`], rebuilt only when the wiggle changes.
+#[derive(Component, Default)]
+struct WiggleNoise(FastNoiseLite);
+
 impl Wiggle {
     pub fn with_offset(offset: Vec2) -> Self {
         Self {
@@ -28,6 +49,12 @@ impl Wiggle {
             ..default()
         }
     }
+
+    /// The updates per second, or `None` if the wiggle is continuous.
+    pub fn steps(&self) -> Option<f32> {
+        self.steps_per_second
+            .filter(|steps| steps.is_finite() && *steps > 0.0)
+    }
 }
 
 impl Default for Wiggle {
@@ -38,6 +65,10 @@ impl Default for Wiggle {
             scale_transform: 1.0,
             offset: Vec2::ZERO,
             offset_angle: 0.0,
+            frequency: 3.0,
+            noise: NoiseType::OpenSimplex2,
+            clock: WiggleClock::Virtual,
+            steps_per_second: None,
         }
     }
 }
@@ -53,21 +84,64 @@ fn initialize_wiggle(
     Ok(())
 }
 
-fn update_wiggle(time: Res<Time>, query: Query<(&mut Transform, &Wiggle)>) {
-    for (mut transform, wiggle) in query {
-        let mut noise = FastNoiseLite::with_seed(wiggle.seed);
-        noise.frequency = 3.0;
+fn update_wiggle(
+    time: Res<Time>,
+    time_real: Res<Time<Real>>,
+    // sprites on the GPU path wiggle in the shader
+    query: Query<(&mut Transform, Ref<Wiggle>, &mut WiggleNoise), Without<EffectSprite>>,
+) {
+    for (mut transform, wiggle, mut noise) in query {
+        if wiggle.is_changed() {
+            noise.0 = FastNoiseLite::with_seed(wiggle.seed);
+            noise.0.set_noise_type(Some(wiggle.noise));
+            noise.0.frequency = wiggle.frequency;
+        }
+
+        let mut elapsed = match wiggle.clock {
+            WiggleClock::Virtual => time.elapsed_secs(),
+            WiggleClock::Real => time_real.elapsed_secs(),
+        };
+
+        // hold each pose for a full step
+        if let Some(steps) = wiggle.steps() {
+            elapsed = (elapsed * steps).floor() / steps;
+        }
+
+        let noise = &noise.0;
 
         // get random rotation and scale it with the wiggle factor
-        let amount = noise.get_noise_2d(time.elapsed_secs(), 0.0);
+        let amount = noise.get_noise_2d(elapsed, 0.0);
         let rotation = wiggle.offset_angle + amount * wiggle.scale_rotation;
 
         // offset by +/- one pixel
-        let wiggle_x = noise.get_noise_2d(time.elapsed_secs(), 10.0);
-        let wiggle_y = noise.get_noise_2d(time.elapsed_secs(), 20.0);
+        let wiggle_x = noise.get_noise_2d(elapsed, 10.0);
+        let wiggle_y = noise.get_noise_2d(elapsed, 20.0);
 
         transform.rotation = Quat::from_rotation_z(rotation);
         transform.translation.x = wiggle.offset.x + wiggle_x * wiggle.scale_transform;
         transform.translation.y = wiggle.offset.y + wiggle_y * wiggle.scale_transform;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_of_zero_or_less_wiggle_continuously() {
+        let steps = |steps_per_second| {
+            Wiggle {
+                steps_per_second,
+                ..default()
+            }
+            .steps()
+        };
+
+        for invalid in [0.0, -4.0, f32::NAN, f32::INFINITY] {
+            assert_eq!(steps(Some(invalid)), None, "accepted {invalid}");
+        }
+
+        assert_eq!(steps(None), None);
+        assert_eq!(steps(Some(4.0)), Some(4.0));
+    }
+}
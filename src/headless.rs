@@ -0,0 +1,571 @@
+//! Simulates a level without a window or GPU, e.g. to regression-test levels.
+//!
+//! ```text
+//! gravitate simulate <level.ron> [burns.ron] [--max-secs <seconds>] [--config <game.config.ron>]
+//! gravitate solve <level.ron> [--max-secs <seconds>] [--config <game.config.ron>]
+//! ```
+//!
+//! `simulate` loads a level file (see `game::level_file`) and a list of
+//! scripted burns, runs the physics at a fixed 64 steps per second and prints
+//! the outcome as JSON. `solve` asks the autopilot for burns that reach the
+//! goal and checks them the same way, e.g. to validate that a level can be
+//! solved. Both exit with code 0 if the goal was reached, 1 if it was not and
+//! 2 if the input could not be loaded.
+//!
+//! Without `--config`, the game config is read from `assets/` below the working
+//! directory, falling back to the defaults with a warning if it is not there.
+//!
+//! Release builds on Windows have no console of their own. They attach to the
+//! console of the shell they were started from, so the report is printed there.
+
+use crate::game;
+use crate::game::autopilot::{PlannedBurn, Problem};
+use crate::game::config::{CONFIG_PATH, GameConfig};
+use crate::game::goal::{GoalReached, Pickup};
+use crate::game::input::{OnJettison, OnThurst};
+use crate::game::landing::Landed;
+use crate::game::level;
+use crate::game::level_file::LevelFile;
+use crate::game::player::Player;
+use crate::game::rocket::{FuelTank, Stages};
+use crate::screens::Screen;
+use avian2d::prelude::LinearVelocity;
+use bevy::ecs::schedule::ExecutorKind;
+use bevy::prelude::*;
+use bevy::scene::ScenePlugin;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+
+/// Simulation steps per second, the same as the fixed timestep of the game.
+pub const STEPS_PER_SECOND: f32 = 64.0;
+
+/// How long a simulation runs if no limit is given.
+const DEFAULT_MAX_SECS: f32 = 60.0;
+
+/// Burns to replay during a simulation.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct BurnScript {
+    pub burns: Vec<ScriptedBurn>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct ScriptedBurn {
+    /// Seconds since the start of the level.
+    pub at: f32,
+    /// Direction of thrust, does not need to be normalized.
+    pub direction: [f32; 2],
+    /// Burn time in seconds.
+    pub duration: f32,
+    /// Drops the burning stage right before this burn.
+    #[serde(default)]
+    pub jettison: bool,
+}
+
+impl BurnScript {
+    /// Rejects burns that can not be replayed, e.g. with an infinite duration.
+    pub fn validate(&self) -> Result<(), String> {
+        for (idx, burn) in self.burns.iter().enumerate() {
+            if !burn.at.is_finite() {
+                return Err(format!("burns[{idx}].at must be a finite number"));
+            }
+
+            if !burn.direction.iter().all(|value| value.is_finite()) {
+                return Err(format!("burns[{idx}].direction must be finite"));
+            }
+
+            if Duration::try_from_secs_f32(burn.duration).is_err() {
+                return Err(format!(
+                    "burns[{idx}].duration must be a non-negative number of seconds, got {}",
+                    burn.duration
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl From<PlannedBurn> for ScriptedBurn {
+    fn from(burn: PlannedBurn) -> Self {
+        Self {
+            at: burn.at,
+            direction: burn.direction.to_array(),
+            duration: burn.duration,
+            jettison: false,
+        }
+    }
+}
+
+/// The outcome of a simulation.
+#[derive(Serialize, Clone, Debug)]
+pub struct SimulationReport {
+    pub goal_reached: bool,
+    /// Seconds until the goal was reached.
+    pub goal_reached_at: Option<f32>,
+    /// Seconds that were simulated.
+    pub time: f32,
+    pub position: [f32; 2],
+    pub velocity: [f32; 2],
+    /// Fuel left in the tank of the burning stage, in seconds.
+    pub fuel_secs: f32,
+    /// Index of the burning stage.
+    pub stage: usize,
+    pub landed: bool,
+    pub pickups_left: usize,
+}
+
+/// Sets up an app that runs the physics of the game without rendering.
+pub fn plugin(app: &mut App) {
+    app.add_plugins((
+        MinimalPlugins,
+        StatesPlugin,
+        TransformPlugin,
+        // avian looks for colliders in scenes
+        AssetPlugin::default(),
+        ScenePlugin,
+        avian2d::PhysicsPlugins::default(),
+    ));
+
+    // advance by exactly one fixed step per update, so results are reproducible
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+        1.0 / STEPS_PER_SECOND,
+    )));
+
+    app.insert_state(Screen::Gameplay);
+
+    app.add_plugins((crate::common::headless_plugin, game::headless_plugin));
+
+    crate::configure_app_systems(app);
+
+    // systems that add up forces run in any order on multiple threads,
+    // which makes the results differ in the last bits from run to run
+    for (_, schedule) in app.world_mut().resource_mut::<Schedules>().iter_mut() {
+        schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+    }
+}
+
+/// A level running in a headless app.
+pub struct Simulation {
+    app: App,
+    player: Entity,
+    goal_reached_at: Option<f32>,
+}
+
+impl Simulation {
+    pub fn new(level: &LevelFile, config: GameConfig) -> Self {
+        let mut app = App::new();
+        app.add_plugins(plugin);
+        app.insert_resource(config.clone());
+
+        app.finish();
+        app.cleanup();
+
+        // planets have no images here, so any look will do
+        let looks = level
+            .planets
+            .iter()
+            .map(|planet| planet.assets + 1)
+            .max()
+            .unwrap_or_default();
+
+        let assets = game::Assets::placeholder(looks);
+
+        let world = app.world_mut();
+        level::spawn_level_file(&mut world.commands(), &assets, &config, level);
+        world.flush();
+
+        let player = world
+            .query_filtered::<Entity, With<Player>>()
+            .single(world)
+            .expect("level spawns exactly one player");
+
+        Self {
+            app,
+            player,
+            goal_reached_at: None,
+        }
+    }
+
+    /// Seconds simulated so far.
+    pub fn time(&self) -> f32 {
+        self.app.world().resource::<Time<Virtual>>().elapsed_secs()
+    }
+
+    /// Advances the simulation by a single step.
+    pub fn step(&mut self) {
+        self.app.update();
+
+        if self.goal_reached_at.is_none() && self.goal_reached() {
+            self.goal_reached_at = Some(self.time());
+        }
+    }
+
+    /// Starts a burn, just like releasing the aiming input does.
+    pub fn burn(&mut self, direction: Vec2, duration: Duration) {
+        self.app
+            .world_mut()
+            .entity_mut(self.player)
+            .trigger(OnThurst {
+                direction: direction.normalize_or_zero(),
+                duration,
+            });
+    }
+
+    pub fn jettison(&mut self) {
+        self.app
+            .world_mut()
+            .entity_mut(self.player)
+            .trigger(OnJettison);
+    }
+
+    pub fn goal_reached(&self) -> bool {
+        self.app
+            .world()
+            .entity(self.player)
+            .contains::<GoalReached>()
+    }
+
+    /// Replays the burns until the goal is reached or `max_secs` have passed.
+    pub fn run(&mut self, burns: &[ScriptedBurn], max_secs: f32) -> SimulationReport {
+        let mut burns = burns.to_vec();
+        burns.sort_by(|a, b| a.at.total_cmp(&b.at));
+
+        let mut pending = burns.into_iter().peekable();
+
+        while self.time() < max_secs && !self.goal_reached() {
+            while let Some(burn) = pending.next_if(|burn| burn.at <= self.time()) {
+                if burn.jettison {
+                    self.jettison();
+                }
+
+                // burn files are validated when loading, see `BurnScript::validate`
+                let duration = Duration::try_from_secs_f32(burn.duration).unwrap_or_default();
+                self.burn(Vec2::from(burn.direction), duration);
+            }
+
+            self.step();
+        }
+
+        self.report()
+    }
+
+    pub fn report(&self) -> SimulationReport {
+        let player = self.app.world().entity(self.player);
+
+        let transform = player.get::<Transform>().unwrap();
+        let velocity = player.get::<LinearVelocity>().unwrap();
+        let tank = player.get::<FuelTank>().unwrap();
+        let stages = player.get::<Stages>().unwrap();
+
+        let pickups_left = self
+            .app
+            .world()
+            .iter_entities()
+            .filter(|entity| entity.contains::<Pickup>())
+            .count();
+
+        SimulationReport {
+            goal_reached: self.goal_reached(),
+            goal_reached_at: self.goal_reached_at,
+            time: self.time(),
+            position: transform.translation.xy().to_array(),
+            velocity: velocity.0.to_array(),
+            fuel_secs: tank.remaining.as_secs(),
+            stage: stages.active_index(),
+            landed: player.contains::<Landed>(),
+            pickups_left,
+        }
+    }
+}
+
+/// The burns the autopilot found and how they worked out in the simulation.
+#[derive(Serialize, Clone, Debug)]
+pub struct SolveReport {
+    pub solved: bool,
+    /// Burn time the autopilot planned with, in seconds.
+    pub fuel: Option<f32>,
+    pub burns: Vec<ScriptedBurn>,
+    pub simulation: Option<SimulationReport>,
+}
+
+const USAGE: &str = "usage: gravitate simulate <level.ron> [burns.ron] [--max-secs <seconds>] [--config <game.config.ron>]\n       \
+    gravitate solve <level.ron> [--max-secs <seconds>] [--config <game.config.ron>]";
+
+/// Entry point of `gravitate simulate`, see the module docs.
+pub fn main(args: &[String]) -> AppExit {
+    let (level, burns, max_secs, config) = match parse_args(args) {
+        Ok(inputs) => inputs,
+        Err(err) => return usage_error(&err),
+    };
+
+    let burns = burns.unwrap_or_default();
+    let report = Simulation::new(&level, config).run(&burns.burns, max_secs);
+
+    print_report(&report, report.goal_reached)
+}
+
+/// Entry point of `gravitate solve`, see the module docs.
+pub fn solve(args: &[String]) -> AppExit {
+    let (level, max_secs, config) = match parse_args(args) {
+        Ok((level, None, max_secs, config)) => (level, max_secs, config),
+        Ok((_, Some(_), _, _)) => return usage_error("solve does not take a burn list"),
+        Err(err) => return usage_error(&err),
+    };
+
+    let Some(problem) = Problem::from_level(&level, &config) else {
+        return usage_error("The level has no goal");
+    };
+
+    let Some(plan) = problem.solve() else {
+        let report = SolveReport {
+            solved: false,
+            fuel: None,
+            burns: Vec::new(),
+            simulation: None,
+        };
+
+        return print_report(&report, false);
+    };
+
+    let burns: Vec<ScriptedBurn> = plan.burns.into_iter().map(ScriptedBurn::from).collect();
+    let simulation = Simulation::new(&level, config).run(&burns, max_secs);
+
+    let report = SolveReport {
+        solved: simulation.goal_reached,
+        fuel: Some(plan.fuel),
+        burns,
+        simulation: Some(simulation),
+    };
+
+    print_report(&report, report.solved)
+}
+
+fn usage_error(err: &str) -> AppExit {
+    eprintln!("{err}");
+    eprintln!("{USAGE}");
+    AppExit::from_code(2)
+}
+
+fn print_report(report: &impl Serialize, success: bool) -> AppExit {
+    match serde_json::to_string_pretty(report) {
+        Ok(json) => println!("{json}"),
+        Err(err) => {
+            eprintln!("Failed to write report: {err}");
+            return AppExit::from_code(2);
+        }
+    }
+
+    if success {
+        AppExit::Success
+    } else {
+        AppExit::from_code(1)
+    }
+}
+
+type Inputs = (LevelFile, Option<BurnScript>, f32, GameConfig);
+
+fn parse_args(args: &[String]) -> Result<Inputs, String> {
+    let mut paths = Vec::new();
+    let mut max_secs = DEFAULT_MAX_SECS;
+    let mut config_path = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--max-secs" {
+            let value = args.next().ok_or("--max-secs needs a value")?;
+            max_secs = value
+                .parse()
+                .map_err(|err| format!("Invalid --max-secs {value:?}: {err}"))?;
+
+            if !max_secs.is_finite() {
+                return Err(format!("Invalid --max-secs {value:?}: must be finite"));
+            }
+        } else if arg == "--config" {
+            config_path = Some(args.next().ok_or("--config needs a path")?);
+        } else {
+            paths.push(arg);
+        }
+    }
+
+    let (level_path, burns_path) = match paths.as_slice() {
+        [level] => (level, None),
+        [level, burns] => (level, Some(burns)),
+        _ => return Err("Expected a level file and an optional burn list".to_owned()),
+    };
+
+    let level = LevelFile::load(level_path)
+        .map_err(|err| format!("Failed to load level {level_path}: {err}"))?;
+
+    let burns = match burns_path {
+        Some(path) => Some(
+            std::fs::read_to_string(path)
+                .map_err(|err| err.to_string())
+                .and_then(|source| {
+                    let burns: BurnScript =
+                        ron::from_str(&source).map_err(|err| err.to_string())?;
+                    burns.validate()?;
+                    Ok(burns)
+                })
+                .map_err(|err| format!("Failed to load burns {path}: {err}"))?,
+        ),
+        None => None,
+    };
+
+    let config = load_config(config_path.map(Path::new))
+        .map_err(|err| format!("Failed to load game config: {err}"))?;
+
+    Ok((level, burns, max_secs, config))
+}
+
+/// Reads the given config, or the one the game would use, falling back to the defaults.
+fn load_config(path: Option<&Path>) -> Result<GameConfig, Box<dyn std::error::Error>> {
+    let path = match path {
+        Some(path) => path.to_owned(),
+        None => {
+            let path = Path::new("assets").join(CONFIG_PATH);
+            if !path.exists() {
+                eprintln!(
+                    "No game config at {}, using the defaults. Pass --config to use another file.",
+                    path.display()
+                );
+
+                return Ok(GameConfig::default());
+            }
+
+            path
+        }
+    };
+
+    let config: GameConfig = ron::from_str(&std::fs::read_to_string(path)?)?;
+    config.validate()?;
+
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::level_file::{GoalDef, PickupDef};
+    use crate::game::rocket::AttitudeControl;
+
+    /// A goal straight above the start, with a pickup on the way.
+    fn straight_up() -> LevelFile {
+        LevelFile {
+            start: [0.0, 0.0],
+            planets: Vec::new(),
+            goal: Some(GoalDef {
+                position: [0.0, 600.0],
+                radius: 64.0,
+            }),
+            pickups: vec![PickupDef {
+                position: [0.0, 300.0],
+                fuel_secs: 5,
+            }],
+            ..Default::default()
+        }
+    }
+
+    const BURN_UP: ScriptedBurn = ScriptedBurn {
+        at: 0.5,
+        direction: [0.0, 1.0],
+        duration: 1.0,
+        jettison: false,
+    };
+
+    #[test]
+    fn scripted_burn_reaches_goal() {
+        let mut simulation = Simulation::new(&straight_up(), GameConfig::default());
+        let report = simulation.run(&[BURN_UP], 60.0);
+
+        assert!(report.goal_reached);
+        assert_eq!(report.goal_reached_at, Some(report.time));
+        assert_eq!(report.pickups_left, 0);
+    }
+
+    #[test]
+    fn without_burns_goal_is_missed() {
+        let mut simulation = Simulation::new(&straight_up(), GameConfig::default());
+        let report = simulation.run(&[], 5.0);
+
+        assert!(!report.goal_reached);
+        assert_eq!(report.position, [0.0, 0.0]);
+        assert_eq!(report.time, 5.0);
+    }
+
+    #[test]
+    fn rejects_burns_that_can_not_be_replayed() {
+        for duration in [-1.0, f32::INFINITY, f32::NAN, f32::MAX] {
+            let burns = BurnScript {
+                burns: vec![ScriptedBurn {
+                    duration,
+                    ..BURN_UP
+                }],
+            };
+
+            assert!(burns.validate().is_err(), "accepted {duration}");
+        }
+
+        let burns = BurnScript {
+            burns: vec![ScriptedBurn {
+                at: f32::NAN,
+                ..BURN_UP
+            }],
+        };
+
+        assert!(burns.validate().is_err());
+    }
+
+    #[test]
+    fn simulations_are_reproducible() {
+        let level = LevelFile::sandbox();
+
+        let burns = [ScriptedBurn {
+            at: 0.0,
+            direction: [0.6, 1.0],
+            duration: 3.0,
+            jettison: false,
+        }];
+
+        let first = Simulation::new(&level, GameConfig::default()).run(&burns, 10.0);
+        let second = Simulation::new(&level, GameConfig::default()).run(&burns, 10.0);
+
+        assert_eq!(first.position, second.position);
+        assert_eq!(first.velocity, second.velocity);
+    }
+
+    #[test]
+    fn autopilot_solves_sandbox() {
+        let level = LevelFile::sandbox();
+        let config = GameConfig::default();
+
+        let plan = Problem::from_level(&level, &config)
+            .and_then(|problem| problem.solve())
+            .expect("sandbox can be solved");
+
+        let burns: Vec<ScriptedBurn> = plan.burns.into_iter().map(ScriptedBurn::from).collect();
+        let report = Simulation::new(&level, config).run(&burns, 60.0);
+
+        assert!(report.goal_reached);
+    }
+
+    #[test]
+    fn autopilot_solves_sandbox_with_realistic_rules() {
+        let mut level = LevelFile::sandbox();
+        level.rules.attitude = AttitudeControl::REALISTIC;
+        level.rules.fuel_has_mass = true;
+
+        let config = GameConfig::default();
+
+        let plan = Problem::from_level(&level, &config)
+            .and_then(|problem| problem.solve())
+            .expect("sandbox can be solved with realistic rules");
+
+        let burns: Vec<ScriptedBurn> = plan.burns.into_iter().map(ScriptedBurn::from).collect();
+        let report = Simulation::new(&level, config).run(&burns, 60.0);
+
+        assert!(report.goal_reached);
+    }
+}
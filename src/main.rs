@@ -11,15 +11,53 @@ use bevy::{asset::AssetMetaCheck, prelude::*};
 pub mod asset_tracking;
 pub mod common;
 pub mod game;
+pub mod headless;
 pub mod menus;
 pub mod player_name;
+pub mod preprocess;
 pub mod screens;
+#[cfg(test)]
+mod testing;
 pub mod ui;
 
 fn main() -> AppExit {
+    // `gravitate simulate ...` runs a level without a window, see `headless`.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command: Option<fn(&[String]) -> AppExit> = match args.first().map(String::as_str) {
+        Some("simulate") => Some(headless::main),
+        Some("solve") => Some(headless::solve),
+        Some("preprocess") => Some(preprocess::main),
+        _ => None,
+    };
+
+    if let Some(command) = command {
+        attach_console();
+        return command(&args[1..]);
+    }
+
     App::new().add_plugins(app_plugin).run()
 }
 
+/// Release builds on Windows are started without a console, see the top of this file.
+/// Commands print to the console of the shell they were started from instead.
+#[cfg(all(windows, not(feature = "dev")))]
+fn attach_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+
+    // does nothing if the parent has no console, e.g. when started from the explorer
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(all(windows, not(feature = "dev"))))]
+fn attach_console() {}
+
 const WORLD_SCALE: f32 = 2.0;
 
 fn app_plugin(app: &mut App) {
@@ -71,6 +109,14 @@ fn app_plugin(app: &mut App) {
         game::plugin,
     ));
 
+    configure_app_systems(app);
+
+    // Spawn the main camera.
+    app.add_systems(Startup, spawn_camera);
+}
+
+/// Orders the `AppSystems` sets, shared with the headless app.
+fn configure_app_systems(app: &mut App) {
     // Order new `AppSystems` variants by adding them here:
     app.configure_sets(
         Update,
@@ -82,12 +128,10 @@ fn app_plugin(app: &mut App) {
             PhysicsSet::Sync,
             AppSystems::Update,
             AppSystems::UpdateCamera,
+            AppSystems::UpdateMarkers,
         )
             .chain(),
     );
-
-    // Spawn the main camera.
-    app.add_systems(Startup, spawn_camera);
 }
 
 /// High-level groupings of systems for the app in the `Update` schedule.
@@ -105,6 +149,8 @@ enum AppSystems {
     Update,
     /// Update the camera
     UpdateCamera,
+    /// Update things that depend on the final camera position
+    UpdateMarkers,
 }
 
 /// Used to help identify our main camera
@@ -2,13 +2,15 @@
 
 use bevy::prelude::*;
 
+use crate::game::camera::ScreenShake;
+use crate::game::level::Level;
 use crate::{asset_tracking::ResourceHandles, menus::Menu, screens::Screen, ui::widget};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Main), spawn_main_menu);
 }
 
-fn spawn_main_menu(mut commands: Commands) {
+fn spawn_main_menu(mut commands: Commands, screen_shake: Res<ScreenShake>) {
     commands.spawn((
         widget::ui_root("Main Menu"),
         GlobalZIndex(2),
@@ -16,20 +18,44 @@ fn spawn_main_menu(mut commands: Commands) {
         #[cfg(not(target_family = "wasm"))]
         children![
             widget::button("Play", enter_loading_or_gameplay_screen),
+            widget::button("Tutorial", enter_tutorial),
+            super::screen_shake_button(&screen_shake),
             widget::button("Exit", exit_app),
         ],
         #[cfg(target_family = "wasm")]
-        children![widget::button("Play", enter_loading_or_gameplay_screen),],
+        children![
+            widget::button("Play", enter_loading_or_gameplay_screen),
+            widget::button("Tutorial", enter_tutorial),
+            super::screen_shake_button(&screen_shake),
+        ],
     ));
 }
 
 fn enter_loading_or_gameplay_screen(
     _: Trigger<Pointer<Click>>,
     resource_handles: Res<ResourceHandles>,
+    mut level: ResMut<Level>,
     mut next_screen: ResMut<NextState<Screen>>,
 ) {
     info!("Button clicked");
 
+    *level = Level::Sandbox;
+
+    if resource_handles.is_all_done() {
+        next_screen.set(Screen::Gameplay);
+    } else {
+        next_screen.set(Screen::Loading);
+    }
+}
+
+fn enter_tutorial(
+    _: Trigger<Pointer<Click>>,
+    resource_handles: Res<ResourceHandles>,
+    mut level: ResMut<Level>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    *level = Level::Tutorial;
+
     if resource_handles.is_all_done() {
         next_screen.set(Screen::Gameplay);
     } else {
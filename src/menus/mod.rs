@@ -3,6 +3,8 @@
 mod main;
 mod pause;
 
+use crate::game::camera::ScreenShake;
+use crate::ui::widget;
 use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
@@ -18,4 +20,29 @@ pub enum Menu {
     None,
     Main,
     Pause,
+    SystemMap,
+}
+
+/// A button that turns camera shake on or off.
+fn screen_shake_button(settings: &ScreenShake) -> impl Bundle {
+    widget::button(screen_shake_text(settings.enabled), toggle_screen_shake)
+}
+
+fn screen_shake_text(enabled: bool) -> String {
+    format!("Screen shake: {}", if enabled { "on" } else { "off" })
+}
+
+fn toggle_screen_shake(
+    trigger: Trigger<Pointer<Click>>,
+    mut settings: ResMut<ScreenShake>,
+    children: Query<&Children>,
+    mut texts: Query<&mut Text>,
+) {
+    settings.enabled = !settings.enabled;
+
+    for child in children.iter_descendants(trigger.target()) {
+        if let Ok(mut text) = texts.get_mut(child) {
+            text.0 = screen_shake_text(settings.enabled);
+        }
+    }
 }
@@ -2,6 +2,7 @@
 
 use bevy::{input::common_conditions::input_just_pressed, prelude::*};
 
+use crate::game::camera::ScreenShake;
 use crate::{menus::Menu, screens::Screen, ui::widget};
 
 pub(super) fn plugin(app: &mut App) {
@@ -13,7 +14,7 @@ pub(super) fn plugin(app: &mut App) {
     );
 }
 
-fn spawn_pause_menu(mut commands: Commands) {
+fn spawn_pause_menu(mut commands: Commands, screen_shake: Res<ScreenShake>) {
     commands.spawn((
         widget::ui_root("Pause Menu"),
         GlobalZIndex(2),
@@ -21,6 +22,7 @@ fn spawn_pause_menu(mut commands: Commands) {
         children![
             widget::header("Game paused"),
             widget::button("Continue", close_menu),
+            super::screen_shake_button(&screen_shake),
             widget::button("Quit to title", quit_to_title),
         ],
     ));
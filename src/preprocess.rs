@@ -0,0 +1,173 @@
+//! Preprocesses the images in `assets/images` so the game does not have to
+//! do it while spawning a level.
+//!
+//! ```text
+//! gravitate preprocess
+//! ```
+//!
+//! * Planet layers are cropped to their visible pixels.
+//! * Every image gets a pre-blurred shadow texture.
+//!
+//! The results and a manifest describing them are written to `assets/processed`
+//! and checked in. Run this again after changing an image, the manifest is
+//! compiled into the game, see `game::processed`.
+
+use crate::common::image_ops;
+use crate::game::processed::{MANIFEST_PATH, ProcessedCrop, ProcessedManifest, ProcessedShadow};
+use bevy::prelude::*;
+use image::imageops;
+use std::fs;
+use std::path::Path;
+
+const ASSETS_DIR: &str = "assets";
+const SOURCE_DIR: &str = "images";
+const TARGET_DIR: &str = "processed";
+
+/// Must match the default sigma of `game::shadow::Shadow`.
+const SHADOW_SIGMA: f32 = 3.0;
+
+/// Images that are cropped before use.
+const CROP_PREFIX: &str = "planet-";
+
+/// Images that are never drawn with a shadow.
+const NO_SHADOW: &[&str] = &["flame", "loader"];
+
+/// Entry point of `gravitate preprocess`, see the module docs.
+pub fn main(args: &[String]) -> AppExit {
+    if !args.is_empty() {
+        eprintln!("preprocess does not take any arguments");
+        return AppExit::from_code(2);
+    }
+
+    match preprocess(Path::new(ASSETS_DIR)) {
+        Ok(manifest) => {
+            println!(
+                "Preprocessed {} crops and {} shadows into {ASSETS_DIR}/{TARGET_DIR}",
+                manifest.crops.len(),
+                manifest.shadows.len(),
+            );
+
+            AppExit::Success
+        }
+
+        Err(err) => {
+            eprintln!("Failed to preprocess images: {err}");
+            AppExit::from_code(1)
+        }
+    }
+}
+
+/// Processes every image in the assets directory and writes the results next to them.
+pub fn preprocess(assets: &Path) -> Result<ProcessedManifest, Box<dyn std::error::Error>> {
+    fs::create_dir_all(assets.join(TARGET_DIR))?;
+
+    let mut sources = source_images(assets)?;
+
+    // keep the manifest stable
+    sources.sort();
+
+    let mut manifest = ProcessedManifest::default();
+
+    for stem in sources {
+        let source = format!("{SOURCE_DIR}/{stem}.png");
+
+        let mut image = image::open(assets.join(&source))
+            .map_err(|err| format!("open {source}: {err}"))?
+            .into_rgba8();
+
+        let mut image_path = source.clone();
+
+        if stem.starts_with(CROP_PREFIX) {
+            let [min_x, min_y, max_x, max_y] = image_ops::find_crop_rect(&image);
+            let (full_width, full_height) = image.dimensions();
+
+            // an empty image is kept as it is
+            let rect = if max_x > min_x && max_y > min_y {
+                [min_x, min_y, max_x, max_y]
+            } else {
+                [0, 0, full_width, full_height]
+            };
+
+            image = imageops::crop_imm(
+                &image,
+                rect[0],
+                rect[1],
+                rect[2] - rect[0],
+                rect[3] - rect[1],
+            )
+            .to_image();
+
+            image_path = format!("{TARGET_DIR}/{stem}.png");
+            image.save(assets.join(&image_path))?;
+
+            manifest.crops.push(ProcessedCrop {
+                source,
+                image: image_path.clone(),
+                rect,
+                full_size: [full_width, full_height],
+            });
+        }
+
+        if NO_SHADOW.contains(&stem.as_str()) {
+            continue;
+        }
+
+        let shadow = image_ops::generate_shadow_from_alpha(&image, SHADOW_SIGMA);
+        let shadow_path = format!("{TARGET_DIR}/{stem}.shadow.png");
+        shadow.save(assets.join(&shadow_path))?;
+
+        manifest.shadows.push(ProcessedShadow {
+            image: image_path,
+            shadow: shadow_path,
+            sigma: SHADOW_SIGMA,
+        });
+    }
+
+    fs::write(assets.join(MANIFEST_PATH), manifest.to_ron()?)?;
+
+    Ok(manifest)
+}
+
+/// Names of the png images to preprocess, without extension.
+pub fn source_images(assets: &Path) -> std::io::Result<Vec<String>> {
+    let mut stems = Vec::new();
+
+    for entry in fs::read_dir(assets.join(SOURCE_DIR))? {
+        let path = entry?.path();
+
+        if path.extension().is_none_or(|ext| ext != "png") {
+            continue;
+        }
+
+        if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+            stems.push(stem.to_owned());
+        }
+    }
+
+    Ok(stems)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::processed;
+
+    #[test]
+    fn checked_in_manifest_covers_all_images() {
+        let images = source_images(Path::new(ASSETS_DIR)).unwrap();
+
+        for stem in images {
+            let source = format!("{SOURCE_DIR}/{stem}.png");
+
+            let has_shadow = NO_SHADOW.contains(&stem.as_str())
+                || processed::crop(&source)
+                    .is_some_and(|crop| processed::shadow(&crop.image, SHADOW_SIGMA).is_some())
+                || processed::shadow(&source, SHADOW_SIGMA).is_some();
+
+            assert!(
+                has_shadow,
+                "{source} was not preprocessed, run `gravitate preprocess`"
+            );
+        }
+    }
+}
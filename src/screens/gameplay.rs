@@ -57,3 +57,57 @@ fn open_pause_menu(mut next_menu: ResMut<NextState<Menu>>) {
 fn close_menu(mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::None);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::camera::ScreenShake;
+    use crate::screens::Screen;
+    use crate::testing::TestApp;
+
+    fn app() -> TestApp {
+        let mut app = TestApp::new().with_plugins((plugin, crate::menus::plugin));
+        app.world_mut().init_resource::<ScreenShake>();
+        app
+    }
+
+    #[test]
+    fn escape_pauses_and_opens_menu() {
+        let mut app = app();
+        app.step();
+
+        app.press(KeyCode::Escape);
+        app.step();
+        app.step();
+
+        assert_eq!(app.state::<Pause>(), Pause(true));
+        assert_eq!(app.state::<Menu>(), Menu::Pause);
+
+        app.release(KeyCode::Escape);
+        app.press(KeyCode::KeyP);
+        app.step();
+        app.step();
+
+        // unpausing follows a frame after the menu closed
+        app.step();
+
+        assert_eq!(app.state::<Menu>(), Menu::None);
+        assert_eq!(app.state::<Pause>(), Pause(false));
+    }
+
+    #[test]
+    fn leaving_gameplay_despawns_level_and_unpauses() {
+        let mut app = app();
+        let planet = app.spawn_attractor(vec2(0.0, -400.0), 128.0);
+        let rocket = app.spawn_rocket(Vec2::ZERO);
+
+        app.set_state(Pause(true));
+        app.set_state(Screen::Title);
+        app.step();
+
+        assert_eq!(app.state::<Screen>(), Screen::Title);
+        assert_eq!(app.state::<Pause>(), Pause(false));
+        assert!(!app.exists(rocket));
+        assert!(!app.exists(planet));
+    }
+}
@@ -3,7 +3,7 @@ use bevy::prelude::{AppExtStates, States};
 
 mod gameplay;
 mod loading;
-mod title;
+pub mod title;
 
 pub fn plugin(app: &mut App) {
     app.init_state::<Screen>();
@@ -18,4 +18,6 @@ pub enum Screen {
     Loading,
     Title,
     Gameplay,
+    /// The level editor, only reachable in dev builds.
+    Editor,
 }
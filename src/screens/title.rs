@@ -17,9 +17,14 @@ fn open_main_menu(mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::Main);
 }
 
+/// The image behind the main menu, hidden while the demo flight plays.
+#[derive(Component)]
+pub struct TitleBackground;
+
 fn spawn_background_image(mut commands: Commands, assets: Res<Assets>) {
     commands.spawn((
         StateScoped(Screen::Title),
+        TitleBackground,
         widget::ui_root("Background"),
         GlobalZIndex(1),
         children![(
@@ -0,0 +1,181 @@
+//! Support for tests that run parts of the game without a window or assets.
+//!
+//! [`TestApp`] builds on the headless app (see `headless`), so time advances
+//! by exactly one fixed step per update and results are reproducible.
+
+use crate::game;
+use crate::game::assets::PlanetAssets;
+use crate::game::config::GameConfig;
+use crate::game::input::OnThurst;
+use crate::game::planet;
+use crate::game::player;
+use crate::game::rocket::{FuelTank, RocketDef};
+use crate::headless;
+use crate::screens::Screen;
+use avian2d::prelude::LinearVelocity;
+use bevy::app::Plugins;
+use bevy::input::ButtonState;
+use bevy::input::InputPlugin;
+use bevy::input::keyboard::{Key, KeyboardInput, NativeKey};
+use bevy::prelude::*;
+use bevy::state::state::FreelyMutableState;
+use std::time::Duration;
+
+pub struct TestApp {
+    app: App,
+    ready: bool,
+}
+
+impl TestApp {
+    /// An app in `Screen::Gameplay` with the systems that move rockets.
+    pub fn new() -> Self {
+        let mut app = App::new();
+        app.add_plugins((headless::plugin, InputPlugin));
+
+        Self { app, ready: false }
+    }
+
+    /// Adds more plugins, must be called before the first step.
+    pub fn with_plugins<M>(mut self, plugins: impl Plugins<M>) -> Self {
+        assert!(!self.ready, "plugins must be added before the first step");
+        self.app.add_plugins(plugins);
+        self
+    }
+
+    pub fn world(&self) -> &World {
+        self.app.world()
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        self.app.world_mut()
+    }
+
+    /// Spawns a planet without any images that attracts rockets.
+    pub fn spawn_attractor(&mut self, position: Vec2, radius: f32) -> Entity {
+        let assets = PlanetAssets { layers: Vec::new() };
+
+        self.world_mut()
+            .spawn((
+                Name::new("Planet"),
+                StateScoped(Screen::Gameplay),
+                Transform::from_translation(position.extend(0.0)),
+                planet::bundle(&assets, radius),
+            ))
+            .id()
+    }
+
+    /// Spawns the player's rocket, which is attracted by planets and reacts to [`OnThurst`].
+    pub fn spawn_rocket(&mut self, position: Vec2) -> Entity {
+        let config = self.world().resource::<GameConfig>().clone();
+        let rocket = RocketDef::two_stage(&game::Assets::placeholder(0), &config);
+
+        self.world_mut()
+            .spawn((
+                Name::new("Player"),
+                StateScoped(Screen::Gameplay),
+                Transform::from_translation(position.extend(0.0)),
+                player::bundle(&rocket),
+            ))
+            .observe(player::handle_on_thrust)
+            .id()
+    }
+
+    /// Starts a burn of the given rocket, just like releasing the aiming input does.
+    pub fn thrust(&mut self, rocket: Entity, direction: Vec2, secs: f32) {
+        self.world_mut().entity_mut(rocket).trigger(OnThurst {
+            direction: direction.normalize(),
+            duration: Duration::from_secs_f32(secs),
+        });
+    }
+
+    /// Presses and holds a key, it counts as just pressed during the next step.
+    pub fn press(&mut self, key_code: KeyCode) {
+        self.send_key(key_code, ButtonState::Pressed);
+    }
+
+    pub fn release(&mut self, key_code: KeyCode) {
+        self.send_key(key_code, ButtonState::Released);
+    }
+
+    fn send_key(&mut self, key_code: KeyCode, state: ButtonState) {
+        self.world_mut().send_event(KeyboardInput {
+            key_code,
+            logical_key: Key::Unidentified(NativeKey::Unidentified),
+            state,
+            text: None,
+            repeat: false,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+
+    /// Runs a single update, which is one step of the physics.
+    pub fn step(&mut self) {
+        if !self.ready {
+            self.app.finish();
+            self.app.cleanup();
+            self.ready = true;
+        }
+
+        self.app.update();
+    }
+
+    /// Runs as many steps as fit into the given duration of game time.
+    pub fn advance(&mut self, duration: Duration) {
+        let steps = (duration.as_secs_f32() * headless::STEPS_PER_SECOND).round() as usize;
+
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+
+    pub fn advance_secs(&mut self, secs: f32) {
+        self.advance(Duration::from_secs_f32(secs));
+    }
+
+    /// Requests a state change and runs a step to apply it.
+    pub fn set_state<S: FreelyMutableState>(&mut self, state: S) {
+        self.world_mut().resource_mut::<NextState<S>>().set(state);
+        self.step();
+    }
+
+    pub fn state<S: States>(&self) -> S {
+        self.world().resource::<State<S>>().get().clone()
+    }
+
+    pub fn position(&self, entity: Entity) -> Vec2 {
+        self.world()
+            .get::<Transform>(entity)
+            .expect("entity has a transform")
+            .translation
+            .xy()
+    }
+
+    pub fn velocity(&self, entity: Entity) -> Vec2 {
+        self.world()
+            .get::<LinearVelocity>(entity)
+            .expect("entity has a velocity")
+            .0
+    }
+
+    /// Seconds of burn time left in the tank of the given rocket.
+    pub fn fuel(&self, rocket: Entity) -> f32 {
+        self.world()
+            .get::<FuelTank>(rocket)
+            .expect("entity has a fuel tank")
+            .remaining
+            .as_secs()
+    }
+
+    pub fn exists(&self, entity: Entity) -> bool {
+        self.world().get_entity(entity).is_ok()
+    }
+}
+
+/// Asserts that two floats are within `epsilon` of each other.
+#[track_caller]
+pub fn assert_near(actual: f32, expected: f32, epsilon: f32) {
+    assert!(
+        (actual - expected).abs() <= epsilon,
+        "expected {expected} ± {epsilon}, got {actual}"
+    );
+}